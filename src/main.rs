@@ -7,12 +7,20 @@ use std::path::Path;
 mod utils;
 
 use utils::{
-    file::create_changelog,
+    config::{ConfigAction, LocalConfig, PackageConfig},
+    file::{create_changelog, create_package_changelog},
     git::{
-        add_project_files, commit_changes, create_tag, get_project_info, initialize_git_repo,
-        reset_tags,
+        add_package_files, add_project_files, commit_changes, create_tag, ensure_release_branch,
+        get_latest_package_tag, get_latest_tag, get_project_info, initialize_git_repo, reset_tags,
+    },
+    github::NotesSource,
+    hooks::run_hooks,
+    project::read_project_versions,
+    tag::TagTemplate,
+    version::{
+        check_version_scheme, get_latest_package_version, get_latest_version, next_version,
+        parse_version_input, update_package_version, update_version_to_project, BumpType,
     },
-    version::{get_latest_version, update_version_to_project, BumpType, Version},
 };
 
 #[derive(Parser)]
@@ -20,6 +28,9 @@ use utils::{
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Override a configuration value for this run (e.g., -c changelog.enabled=false)
+    #[arg(short = 'c', long = "config", global = true, value_name = "KEY=VALUE")]
+    overrides: Vec<String>,
 }
 
 #[derive(Subcommand)]
@@ -31,23 +42,59 @@ enum Commands {
         /// Set specific version instead of bumping
         #[arg(short = 'V', long)]
         version: Option<String>,
+        /// Bump a single package of a monorepo instead of the whole repository
+        #[arg(short, long)]
+        package: Option<String>,
+        /// Warn and continue when a project file cannot be updated
+        #[arg(long)]
+        lenient: bool,
     },
     /// Bump minor version (e.g., 1.0.0 → 1.1.0)
     Minor {
         /// Set specific version instead of bumping
         #[arg(short = 'V', long)]
         version: Option<String>,
+        /// Bump a single package of a monorepo instead of the whole repository
+        #[arg(short, long)]
+        package: Option<String>,
+        /// Warn and continue when a project file cannot be updated
+        #[arg(long)]
+        lenient: bool,
     },
     /// Bump major version (e.g., 1.0.0 → 2.0.0)
     Major {
         /// Set specific version instead of bumping
         #[arg(short = 'V', long)]
         version: Option<String>,
+        /// Bump a single package of a monorepo instead of the whole repository
+        #[arg(short, long)]
+        package: Option<String>,
+        /// Warn and continue when a project file cannot be updated
+        #[arg(long)]
+        lenient: bool,
+    },
+    /// Bump to the next version of the configured scheme (patch for semver, date for CalVer)
+    Bump {
+        /// Set specific version instead of bumping
+        #[arg(short = 'V', long)]
+        version: Option<String>,
+        /// Bump a single package of a monorepo instead of the whole repository
+        #[arg(short, long)]
+        package: Option<String>,
+        /// Warn and continue when a project file cannot be updated
+        #[arg(long)]
+        lenient: bool,
     },
     /// Reset local tags to match the remote repository
     Reset,
     /// Show the current version
     Show,
+    /// Check that project files match the latest tag
+    Check {
+        /// Update mismatched project files to the latest tag version
+        #[arg(long)]
+        fix: bool,
+    },
     /// Create or list releases
     Release {
         /// List all available releases
@@ -56,28 +103,48 @@ enum Commands {
         /// Create a release for the specified version
         #[arg(short = 't', long)]
         tag: Option<String>,
+        /// Where the release notes come from
+        #[arg(short = 'n', long, value_enum, default_value_t = NotesSource::Commits)]
+        notes: NotesSource,
     },
     /// Synchronize local tags with the remote repository
     Sync,
     /// Manage RustyTag configuration settings
     Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
         /// Set a configuration value (e.g., GITHUB_TOKEN=xxx)
         #[arg(short, long)]
         set: Option<String>,
-        /// Set as global configuration
-        #[arg(short, long)]
+        /// Use the global configuration
+        #[arg(short, long, global = true)]
         global: bool,
-        /// Set as local configuration
-        #[arg(short, long)]
+        /// Use the project configuration
+        #[arg(short, long, global = true)]
         local: bool,
+        /// Manage the credentials of a host (e.g., github.corp.com)
+        #[arg(long, global = true)]
+        host: Option<String>,
     },
 }
 
 fn show_project_info(repo: &Repository) -> Result<()> {
     let info = get_project_info(repo)?;
+    let tag = get_latest_tag()?;
+    let manifests = read_project_versions().unwrap_or_else(|e| {
+        println!("⚠️ Failed to read project versions: {}", e);
+        Vec::new()
+    });
     println!("\n📦 Project Information");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("🏷️  Current Version: {}", info.version);
+    match tag {
+        Some(tag) => println!("🔖 Latest Tag: {}", tag),
+        None => println!("🔖 Latest Tag: No tags"),
+    }
+    for (path, version) in manifests {
+        println!("📄 {}: {}", path.display(), version);
+    }
     if let Some(branch) = info.branch_name {
         println!("🌿 Current Branch: {}", branch);
     }
@@ -85,6 +152,17 @@ fn show_project_info(repo: &Repository) -> Result<()> {
     if let Some(url) = info.repo_url {
         println!("🔗 Repository URL: {}", url);
     }
+    let packages = LocalConfig::load()?.packages;
+    if !packages.is_empty() {
+        println!("\n📦 Packages");
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        for package in packages {
+            let version = get_latest_package_tag(repo, &package)?
+                .map(|v| v.version.to_string())
+                .unwrap_or_else(|| "No tags".to_string());
+            println!("🏷️  {} ({}): {}", package.name, package.path, version);
+        }
+    }
     println!("\n🛠️  RustyTag Information");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("📋 Version: {}", env!("CARGO_PKG_VERSION"));
@@ -95,8 +173,48 @@ fn show_project_info(repo: &Repository) -> Result<()> {
     Ok(())
 }
 
+/// 升级 monorepo 中单个包的版本
+fn bump_package(
+    repo: &Repository,
+    name: &str,
+    version: Option<&str>,
+    bump_type: Option<BumpType>,
+    lenient: bool,
+) -> Result<()> {
+    let package = PackageConfig::find(name)?;
+    let latest_version = get_latest_package_version(&package)?;
+    let new_version = match version {
+        Some(version_str) => TagTemplate::for_package(&LocalConfig::load()?, &package)?
+            .parse_input(version_str)
+            .context("Invalid version format")?,
+        None => next_version(&latest_version, bump_type)?,
+    };
+    check_version_scheme(&new_version)?;
+    let previous = get_latest_package_tag(repo, &package)?;
+    let config = LocalConfig::load()?;
+    let hooks = config.hooks.clone().unwrap_or_default();
+
+    ensure_release_branch(repo, &config.branches)?;
+    run_hooks("pre_bump", &hooks.pre_bump, &new_version, &latest_version)?;
+    update_package_version(&package, &new_version, lenient)?;
+    add_package_files(repo, &package)?;
+    let changelog = config
+        .changelog_path()
+        .map(|_| Path::new(&package.path).join("CHANGELOG.md"));
+    if changelog.is_some() {
+        create_package_changelog(&package, &new_version, previous.as_ref())?;
+    }
+    commit_changes(repo, &new_version.to_string(), changelog.as_deref())?;
+    create_tag(repo, &new_version, changelog.as_deref())?;
+    run_hooks("post_bump", &hooks.post_bump, &new_version, &latest_version)?;
+    println!("\nℹ Run the following command to publish the release");
+    println!("git push --follow-tags origin main\n");
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    utils::layers::set_cli_overrides(&cli.overrides)?;
     match cli.command {
         Commands::Init => {
             handle_git_initialization()?;
@@ -109,26 +227,65 @@ fn main() -> Result<()> {
             }
             let repo = Repository::open(".").context("Failed to open Git repository")?;
             match &cli.command {
-                Commands::Patch { version } | Commands::Minor { version } | Commands::Major { version } => {
+                Commands::Patch {
+                    version,
+                    package,
+                    lenient,
+                }
+                | Commands::Minor {
+                    version,
+                    package,
+                    lenient,
+                }
+                | Commands::Major {
+                    version,
+                    package,
+                    lenient,
+                }
+                | Commands::Bump {
+                    version,
+                    package,
+                    lenient,
+                } => {
                     let bump_type = match cli.command {
-                        Commands::Patch { .. } => BumpType::Patch,
-                        Commands::Minor { .. } => BumpType::Minor,
-                        Commands::Major { .. } => BumpType::Major,
+                        Commands::Patch { .. } => Some(BumpType::Patch),
+                        Commands::Minor { .. } => Some(BumpType::Minor),
+                        Commands::Major { .. } => Some(BumpType::Major),
+                        Commands::Bump { .. } => None,
                         _ => unreachable!(),
                     };
-                    
+
+                    if let Some(name) = package {
+                        bump_package(&repo, name, version.as_deref(), bump_type, *lenient)?;
+                        return Ok(());
+                    }
+
+                    let latest_version = get_latest_version()?;
                     let new_version = if let Some(version_str) = version {
-                        Version::parse(version_str).context("Invalid version format")?
+                        parse_version_input(version_str).context("Invalid version format")?
                     } else {
-                        let latest_version = get_latest_version()?;
-                        latest_version.bump(bump_type)
+                        next_version(&latest_version, bump_type)?
                     };
-                    
-                    update_version_to_project(&new_version)?;
+                    check_version_scheme(&new_version)?;
+
+                    let config = LocalConfig::load()?;
+                    let hooks = config.hooks.clone().unwrap_or_default();
+                    ensure_release_branch(&repo, &config.branches)?;
+                    run_hooks("pre_bump", &hooks.pre_bump, &new_version, &latest_version)?;
+
+                    update_version_to_project(&new_version, &latest_version, *lenient)?;
                     add_project_files(&repo)?;
-                    create_changelog(&new_version)?;
-                    commit_changes(&repo, &new_version)?;
-                    create_tag(&repo, &new_version)?;
+                    let changelog = config.changelog_path();
+                    if let Some(changelog) = &changelog {
+                        create_changelog(&new_version, changelog)?;
+                    }
+                    commit_changes(
+                        &repo,
+                        &new_version.version.to_string(),
+                        changelog.as_deref(),
+                    )?;
+                    create_tag(&repo, &new_version, changelog.as_deref())?;
+                    run_hooks("post_bump", &hooks.post_bump, &new_version, &latest_version)?;
                     println!("\nℹ Run the following command to publish the release");
                     println!("git push --follow-tags origin main\n");
                 }
@@ -138,13 +295,16 @@ fn main() -> Result<()> {
                 Commands::Show => {
                     show_project_info(&repo)?;
                 }
-                Commands::Release { tag, list } => {
+                Commands::Check { fix } => {
+                    utils::check::check_versions(*fix)?;
+                }
+                Commands::Release { tag, list, notes } => {
                     if *list {
                         tokio::runtime::Runtime::new()?
                             .block_on(async { utils::github::list_github_releases().await })?;
                     } else {
                         let version = if let Some(tag_str) = tag {
-                            Version::parse(tag_str).context("Invalid version format")?
+                            parse_version_input(tag_str).context("Invalid version format")?
                         } else {
                             get_latest_version()?
                         };
@@ -160,7 +320,7 @@ fn main() -> Result<()> {
 
                         if input.trim().to_lowercase() == "y" {
                             tokio::runtime::Runtime::new()?.block_on(async {
-                                utils::github::create_github_release(&version).await
+                                utils::github::create_github_release(&version, *notes).await
                             })?;
                         } else {
                             println!("❌ Release cancelled");
@@ -170,8 +330,20 @@ fn main() -> Result<()> {
                 Commands::Sync => {
                     utils::git::show_and_sync_tags(&repo)?;
                 }
-                Commands::Config { set, global, local } => {
-                    utils::config::handle_config_command(set.clone(), *global, *local)?;
+                Commands::Config {
+                    action,
+                    set,
+                    global,
+                    local,
+                    host,
+                } => {
+                    utils::config::handle_config_command(
+                        action.clone(),
+                        set.clone(),
+                        *global,
+                        *local,
+                        host.clone(),
+                    )?;
                 }
                 _ => unreachable!(),
             }
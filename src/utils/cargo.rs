@@ -0,0 +1,354 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{Document, Item};
+
+use super::config::LocalConfig;
+use super::edit::FileEdits;
+use super::version::{set_version_value, toml_path_mut, Version};
+
+// Cargo workspace 版本管理
+//
+// 根 Cargo.toml 之外，还需要同步：
+// - `[workspace.package].version`
+// - 各成员 crate 的 `[package].version`（`version.workspace = true` 的除外）
+// - 工作区内 `path` 依赖的版本要求
+// - Cargo.lock 中工作区 crate 的版本（不调用 cargo）
+
+/// 依赖表的名称，包括 `[target.'cfg(..)'.dependencies]` 中的同名表
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// 获取工作区内所有成员的 Cargo.toml 路径（不含根目录）
+pub fn workspace_members(root: &Path) -> Result<Vec<PathBuf>> {
+    let manifest = root.join("Cargo.toml");
+    let doc = fs::read_to_string(&manifest)?.parse::<Document>()?;
+    let Some(workspace) = doc.get("workspace") else {
+        return Ok(Vec::new());
+    };
+
+    let patterns = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let excluded: HashSet<PathBuf> = patterns("exclude")
+        .iter()
+        .map(|p| normalize(&root.join(p)))
+        .collect();
+
+    let mut members = Vec::new();
+    for pattern in patterns("members") {
+        let pattern = root.join(&pattern);
+        for dir in glob::glob(&pattern.to_string_lossy())?.flatten() {
+            let member = dir.join("Cargo.toml");
+            if normalize(&dir) == normalize(root)
+                || excluded.contains(&normalize(&dir))
+                || !member.exists()
+            {
+                continue;
+            }
+            if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+    }
+    Ok(members)
+}
+
+/// 去掉路径中的 `.`，使 glob 结果与配置中的路径可以直接比较
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .collect()
+}
+
+/// 更新根目录及所有工作区成员的版本号，返回修改前的版本号
+///
+/// `packages` 中配置的独立版本的包只更新其依赖要求，不修改其版本号。
+/// 修改暂存在 `edits` 中；根目录和所有成员都没有声明版本时返回错误。
+pub fn update_workspace(root: &Path, version: &Version, edits: &mut FileEdits) -> Result<String> {
+    let new_version = version.version.to_string();
+    let root_manifest = root.join("Cargo.toml");
+    let members = workspace_members(root)?;
+    let packages = LocalConfig::load()?.packages;
+
+    let mut manifests = Vec::new();
+    for path in std::iter::once(&root_manifest).chain(members.iter()) {
+        let content = edits.read(path)?;
+        let doc = content
+            .parse::<Document>()
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        let independent = path != &root_manifest && packages.iter().any(|p| p.contains(path));
+        manifests.push((path.clone(), doc, independent));
+    }
+
+    // 随仓库版本升级的 crate 名称，用于识别内部依赖和 Cargo.lock 条目
+    let crate_names: HashSet<String> = manifests
+        .iter()
+        .filter(|(_, _, independent)| !independent)
+        .filter_map(|(_, doc, _)| doc.get("package")?.get("name")?.as_str().map(String::from))
+        .collect();
+
+    let mut previous = None;
+    for (_, doc, independent) in &mut manifests {
+        let root = doc.as_item_mut();
+        for table in [&["package"][..], &["workspace", "package"]] {
+            if *independent {
+                break;
+            }
+            if let Some(item) = toml_path_mut(root, table)
+                .and_then(|t| t.as_table_like_mut())
+                .and_then(|t| t.get_mut("version"))
+                .filter(|item| item.is_str())
+            {
+                previous.get_or_insert_with(|| item.as_str().unwrap_or("").to_string());
+                set_version_value(item, &new_version);
+            }
+        }
+
+        update_dependency_requirements(root, &crate_names, &new_version);
+    }
+
+    let previous = previous
+        .ok_or_else(|| anyhow::anyhow!("No version found in {}", root_manifest.display()))?;
+    for (path, doc, _) in manifests {
+        edits.write(&path, doc.to_string());
+    }
+    update_cargo_lock(&root.join("Cargo.lock"), &crate_names, &new_version, edits)?;
+    Ok(previous)
+}
+
+/// 查找以 `manifest` 为成员的 Cargo 工作区根目录，不是任何工作区的成员时返回 `None`
+///
+/// 与 Cargo 一样使用向上找到的第一个声明了 `[workspace]` 的 Cargo.toml。
+pub fn workspace_root(manifest: &Path) -> Result<Option<PathBuf>> {
+    let manifest = normalize(manifest);
+    let mut dir = manifest.parent();
+    while let Some(current) = dir {
+        dir = current.parent();
+        let candidate = current.join("Cargo.toml");
+        if candidate == manifest || !candidate.exists() {
+            continue;
+        }
+        let doc = fs::read_to_string(&candidate)?.parse::<Document>()?;
+        if doc.get("workspace").is_none() {
+            continue;
+        }
+        let is_member = workspace_members(current)?
+            .iter()
+            .any(|member| normalize(member) == manifest);
+        return Ok(is_member.then(|| current.to_path_buf()));
+    }
+    Ok(None)
+}
+
+/// 只升级工作区中的一个成员 crate，返回修改前的版本号
+///
+/// 用于 monorepo 中独立版本的包：修改该 crate 的 `[package].version`
+/// （`version.workspace = true` 改为单独的版本号）、其他成员对它的依赖要求，
+/// 以及根目录 Cargo.lock 中它的条目。修改暂存在 `edits` 中。
+pub fn update_workspace_member(
+    root: &Path,
+    manifest: &Path,
+    version: &Version,
+    edits: &mut FileEdits,
+) -> Result<String> {
+    let new_version = version.version.to_string();
+    let root_manifest = root.join("Cargo.toml");
+
+    let mut doc = edits
+        .read(manifest)?
+        .parse::<Document>()
+        .with_context(|| format!("Failed to parse {}", manifest.display()))?;
+    let package = doc
+        .get_mut("package")
+        .and_then(|p| p.as_table_like_mut())
+        .ok_or_else(|| anyhow::anyhow!("No [package] found in {}", manifest.display()))?;
+    let name = package
+        .get("name")
+        .and_then(|n| n.as_str())
+        .ok_or_else(|| anyhow::anyhow!("No package name found in {}", manifest.display()))?
+        .to_string();
+    let previous = match package.get_mut("version") {
+        Some(item) if item.is_str() => {
+            let previous = item.as_str().unwrap_or("").to_string();
+            set_version_value(item, &new_version);
+            previous
+        }
+        // 继承工作区版本时改为单独的版本号
+        Some(_) => {
+            let previous = edits
+                .read(&root_manifest)?
+                .parse::<Document>()?
+                .get("workspace")
+                .and_then(|w| w.get("package"))
+                .and_then(|p| p.get("version"))
+                .and_then(|v| v.as_str())
+                .map(String::from)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "No [workspace.package] version found in {}",
+                        root_manifest.display()
+                    )
+                })?;
+            package.insert("version", toml_edit::value(new_version.clone()));
+            previous
+        }
+        None => {
+            return Err(anyhow::anyhow!(
+                "No version found in {}",
+                manifest.display()
+            ))
+        }
+    };
+    edits.write(manifest, doc.to_string());
+
+    let crate_names = HashSet::from([name]);
+    for path in std::iter::once(root_manifest.clone()).chain(workspace_members(root)?) {
+        let content = edits.read(&path)?;
+        let mut doc = content
+            .parse::<Document>()
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        update_dependency_requirements(doc.as_item_mut(), &crate_names, &new_version);
+        if doc.to_string() != content {
+            edits.write(&path, doc.to_string());
+        }
+    }
+    update_cargo_lock(&root.join("Cargo.lock"), &crate_names, &new_version, edits)?;
+    Ok(previous)
+}
+
+/// 更新指向工作区内 crate 的 `path` 依赖的版本要求
+fn update_dependency_requirements(root: &mut Item, crate_names: &HashSet<String>, version: &str) {
+    let mut tables: Vec<&mut Item> = Vec::new();
+    let Some(root) = root.as_table_like_mut() else {
+        return;
+    };
+    for (key, item) in root.iter_mut() {
+        match key.get() {
+            "workspace" => {
+                if let Some(deps) = item
+                    .as_table_like_mut()
+                    .and_then(|t| t.get_mut("dependencies"))
+                {
+                    tables.push(deps);
+                }
+            }
+            "target" => {
+                if let Some(targets) = item.as_table_like_mut() {
+                    for (_, target) in targets.iter_mut() {
+                        if let Some(target) = target.as_table_like_mut() {
+                            for (name, deps) in target.iter_mut() {
+                                if DEPENDENCY_TABLES.contains(&name.get()) {
+                                    tables.push(deps);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            name if DEPENDENCY_TABLES.contains(&name) => tables.push(item),
+            _ => {}
+        }
+    }
+
+    for deps in tables {
+        let Some(deps) = deps.as_table_like_mut() else {
+            continue;
+        };
+        for (key, dep) in deps.iter_mut() {
+            let Some(dep) = dep.as_table_like_mut() else {
+                continue;
+            };
+            let name = dep
+                .get("package")
+                .and_then(|p| p.as_str())
+                .unwrap_or(key.get())
+                .to_string();
+            if !crate_names.contains(&name) || dep.get("path").is_none() {
+                continue;
+            }
+            if let Some(requirement) = dep.get_mut("version").filter(|v| v.is_str()) {
+                let current = requirement.as_str().unwrap_or("").to_string();
+                match simple_requirement_operator(&current) {
+                    Some(operator) => {
+                        set_version_value(requirement, &format!("{}{}", operator, version))
+                    }
+                    None => println!(
+                        "⚠️  Kept requirement \"{}\" on {}, only simple ^, = or ~ requirements are updated",
+                        current, name
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// 可以直接替换版本号的依赖要求，返回其运算符（可能为空）
+///
+/// 只接受单个 `^`、`=`、`~` 或不带运算符的要求；`=` 与 `~` 必须写全三段版本号，
+/// 否则替换后范围会变窄（例如 `~1` 变为 `~1.2.3`）。`>=1.0, <2` 这类组合要求返回 `None`。
+fn simple_requirement_operator(requirement: &str) -> Option<String> {
+    let parsed = semver::VersionReq::parse(requirement).ok()?;
+    let [comparator] = parsed.comparators.as_slice() else {
+        return None;
+    };
+    let full = comparator.minor.is_some() && comparator.patch.is_some();
+    match comparator.op {
+        semver::Op::Caret => {}
+        semver::Op::Exact | semver::Op::Tilde if full => {}
+        _ => return None,
+    }
+    let operator: String = requirement
+        .trim()
+        .chars()
+        .take_while(|c| !c.is_ascii_digit())
+        .collect();
+    Some(operator)
+}
+
+/// 更新 Cargo.lock 中工作区 crate 的版本号
+///
+/// 只修改没有 `source` 的本地包条目，其余内容保持不变。
+fn update_cargo_lock(
+    path: &Path,
+    crate_names: &HashSet<String>,
+    version: &str,
+    edits: &mut FileEdits,
+) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let mut doc = edits.read(path)?.parse::<Document>()?;
+    let Some(packages) = doc
+        .get_mut("package")
+        .and_then(|p| p.as_array_of_tables_mut())
+    else {
+        return Ok(());
+    };
+
+    for package in packages.iter_mut() {
+        let is_workspace_crate = package
+            .get("name")
+            .and_then(|n| n.as_str())
+            .is_some_and(|name| crate_names.contains(name));
+        if !is_workspace_crate || package.contains_key("source") {
+            continue;
+        }
+        if let Some(item) = package.get_mut("version") {
+            set_version_value(item, version);
+        }
+    }
+
+    edits.write(path, doc.to_string());
+    Ok(())
+}
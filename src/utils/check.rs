@@ -0,0 +1,121 @@
+use anyhow::Result;
+use std::path::Path;
+
+use super::config::LocalConfig;
+use super::edit::FileEdits;
+use super::git::{get_latest_package_tag, get_latest_tag};
+use super::project::ProjectFile;
+use super::version::{update_project_files, Version};
+
+// 版本一致性检查
+//
+// 对比每个项目文件声明的版本号与最新标签，供 CI 在合并前发现漏改的版本号。
+// monorepo 中独立版本的包与其自己的最新标签对比。
+
+/// 按同一个标签检查的一组项目文件
+struct CheckGroup {
+    expected: Version,
+    files: Vec<ProjectFile>,
+}
+
+/// 收集仓库与各个包需要检查的文件，没有标签的部分跳过
+fn check_groups() -> Result<Vec<CheckGroup>> {
+    let mut groups = Vec::new();
+    match get_latest_tag()? {
+        Some(expected) => groups.push(CheckGroup {
+            expected,
+            files: ProjectFile::detect_all()?,
+        }),
+        None => println!("⚠️ No version tags found, skipping repository files"),
+    }
+
+    let repo = git2::Repository::open(".")?;
+    for package in LocalConfig::load()?.packages {
+        match get_latest_package_tag(&repo, &package)? {
+            Some(expected) => groups.push(CheckGroup {
+                expected,
+                files: ProjectFile::detect_in(Path::new(&package.path))?,
+            }),
+            None => println!("⚠️ No tags found for package {}, skipping", package.name),
+        }
+    }
+    Ok(groups)
+}
+
+/// 检查项目文件与最新标签是否一致，`fix` 为真时把不一致的文件改为标签版本
+pub fn check_versions(fix: bool) -> Result<()> {
+    let groups = check_groups()?;
+    if groups.is_empty() {
+        println!("⚠️ No version tags found, nothing to check against");
+        return Ok(());
+    }
+
+    let mut rows = Vec::new();
+    let mut drifted = Vec::new();
+    for group in groups {
+        let mut group_drifted = Vec::new();
+        for file in group.files {
+            let (found, in_sync) = match file.read_version_text() {
+                Ok(Some(found)) => {
+                    let in_sync =
+                        semver::Version::parse(&found).is_ok_and(|v| v == group.expected.version);
+                    (found, in_sync)
+                }
+                // 版本由构建后端动态提供
+                Ok(None) => ("(skipped)".to_string(), true),
+                Err(e) => (format!("({:#})", e), false),
+            };
+            rows.push((
+                file.path.display().to_string(),
+                found,
+                group.expected.to_string(),
+                in_sync,
+            ));
+            if !in_sync {
+                group_drifted.push(file);
+            }
+        }
+        if !group_drifted.is_empty() {
+            drifted.push((group.expected, group_drifted));
+        }
+    }
+
+    let file_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(4);
+    let version_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(7);
+    println!("\n🔍 Version Check");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!(
+        "   {:file_width$}  {:version_width$}  Tag",
+        "File", "Version"
+    );
+    for (path, found, expected, in_sync) in &rows {
+        let mark = if *in_sync { "✔" } else { "✘" };
+        println!(
+            " {} {:file_width$}  {:version_width$}  {}",
+            mark, path, found, expected
+        );
+    }
+    println!();
+
+    let count: usize = drifted.iter().map(|(_, files)| files.len()).sum();
+    if count == 0 {
+        println!("✔ All project files match their latest tags");
+        return Ok(());
+    }
+    if fix {
+        let mut edits = FileEdits::default();
+        for (expected, files) in &drifted {
+            update_project_files(files, expected, false, &mut edits)?;
+        }
+        edits.commit()?;
+        println!(
+            "✔ [Fixed] {} project file(s) aligned to their latest tags",
+            count
+        );
+        return Ok(());
+    }
+    Err(anyhow::anyhow!(
+        "{} project file(s) do not match their latest tags, run `rustytag check --fix` to align them",
+        count
+    ))
+}
@@ -1,125 +1,1209 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use toml_edit::{Document, Item, Table};
 
+use super::layers::{key_origin, load_layers, resolve_key, Layer};
+use super::secret::{self, TokenStore};
+
+// 项目配置
+//
+// 按以下顺序查找，使用第一个存在的配置：
+// - `rustytag.toml`
+// - Cargo.toml 的 `[package.metadata.rustytag]`
+// - pyproject.toml 的 `[tool.rustytag]`
+// - `.rustytag.json`（旧版格式，仍然可以读取）
+//
+// 都不存在时，保存的配置写入 `rustytag.toml`。
+//
+// 读取时与全局配置、环境变量等合并，见 `layers` 模块。
+
+/// 全局配置 `~/.rustytag/config.toml`
+///
+/// 除 GitHub token 外，也可以设置任意项目配置作为所有仓库的默认值。
+/// token 本身保存在系统钥匙串中，见 `secret` 模块。
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct GlobalConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub github_token: Option<String>,
+    /// 从文件读取 token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github_token_file: Option<String>,
+    /// 执行命令获取 token，例如 `gh auth token`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github_token_command: Option<String>,
+    /// token 的保存位置，token 以明文写在配置文件中时为 `None`
+    #[serde(skip)]
+    pub token_store: Option<TokenStore>,
+    /// 按主机名区分的凭据，例如 GitHub Enterprise Server
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hosts: BTreeMap<String, HostConfig>,
+    #[serde(flatten)]
+    pub settings: LocalConfig,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+/// 单个主机的凭据与 API 地址，`[hosts."github.corp.com"]`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HostConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_command: Option<String>,
+    /// API 地址，默认为 `https://<host>/api/v3`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// 主机的代码托管平台
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<Provider>,
+    #[serde(skip)]
+    pub token_store: Option<TokenStore>,
+}
+
+impl HostConfig {
+    /// 主机的 API 地址
+    pub fn api_url(host: &str) -> Result<String> {
+        let configured = GlobalConfig::load()?
+            .hosts
+            .get(host)
+            .and_then(|profile| profile.api_url.clone());
+        Ok(match configured {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None if host == secret::DEFAULT_HOST => "https://api.github.com".to_string(),
+            None => format!("https://{}/api/v3", host),
+        })
+    }
+
+    /// 主机的代码托管平台，未配置时 gitlab.com 与 `gitlab.` 开头的主机视为 GitLab
+    pub fn provider(host: &str) -> Result<Provider> {
+        let configured = GlobalConfig::load()?
+            .hosts
+            .get(host)
+            .and_then(|profile| profile.provider);
+        Ok(match configured {
+            Some(provider) => provider,
+            None if host == "gitlab.com" || host.starts_with("gitlab.") => Provider::Gitlab,
+            None => Provider::Github,
+        })
+    }
+}
+
+/// 主机的代码托管平台，`release` 目前只支持 GitHub
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// github.com 与 GitHub Enterprise Server
+    #[default]
+    Github,
+    Gitlab,
+}
+
+impl std::fmt::Display for Provider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Github => write!(f, "github"),
+            Self::Gitlab => write!(f, "gitlab"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct LocalConfig {
+    /// 标签前缀，例如 `v`，等同于 `tag_template = "v{version}"`
+    #[serde(alias = "prefix", skip_serializing_if = "Option::is_none")]
     pub version_prefix: Option<String>,
+    /// 标签模板，例如 `release-{version}`，见 `tag` 模块
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_template: Option<String>,
+    /// 所有包的标签模板，默认为 `{package}@{version}`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_tag_template: Option<String>,
+    /// 当前版本的来源，默认为标签
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_source: Option<VersionSource>,
+    /// 版本方案，默认为语义化版本
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_scheme: Option<VersionScheme>,
+    /// 允许升级版本的分支，支持 glob；为空时不限制
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<String>,
+    /// CHANGELOG 生成选项
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changelog: Option<ChangelogConfig>,
+    /// 升级前后执行的命令
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<HooksConfig>,
+    /// 升级版本时同时更新 Helm Chart.yaml 的 `appVersion`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub helm_app_version: Option<bool>,
+    /// 自定义的文件版本替换规则
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileReplacer>,
+    /// 递归查找子目录中的项目文件
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discovery: Option<DiscoveryConfig>,
+    /// monorepo 中独立管理版本的包
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageConfig>,
+    /// 配置所在的文件
+    #[serde(skip)]
+    pub source: ConfigSource,
+}
+
+/// 项目配置所在的文件
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// `rustytag.toml`
+    #[default]
+    RustytagToml,
+    /// Cargo.toml 的 `[package.metadata.rustytag]`
+    CargoMetadata,
+    /// pyproject.toml 的 `[tool.rustytag]`
+    PyprojectTool,
+    /// 旧版的 `.rustytag.json`
+    LegacyJson,
+}
+
+impl ConfigSource {
+    const ALL: [Self; 4] = [
+        Self::RustytagToml,
+        Self::CargoMetadata,
+        Self::PyprojectTool,
+        Self::LegacyJson,
+    ];
+
+    pub fn path(&self) -> &'static Path {
+        Path::new(match self {
+            Self::RustytagToml => "rustytag.toml",
+            Self::CargoMetadata => "Cargo.toml",
+            Self::PyprojectTool => "pyproject.toml",
+            Self::LegacyJson => ".rustytag.json",
+        })
+    }
+
+    /// 配置在 TOML 文件中所在的表
+    fn table(&self) -> &'static [&'static str] {
+        match self {
+            Self::CargoMetadata => &["package", "metadata", "rustytag"],
+            Self::PyprojectTool => &["tool", "rustytag"],
+            Self::RustytagToml | Self::LegacyJson => &[],
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CargoMetadata => write!(f, "Cargo.toml [package.metadata.rustytag]"),
+            Self::PyprojectTool => write!(f, "pyproject.toml [tool.rustytag]"),
+            _ => write!(f, "{}", self.path().display()),
+        }
+    }
+}
+
+/// CHANGELOG 生成选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangelogConfig {
+    /// 是否生成 CHANGELOG，默认生成
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// CHANGELOG 路径，默认为 `CHANGELOG.md`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// 升级前后执行的命令
+///
+/// 命令通过 shell 执行，可以使用 `RUSTYTAG_VERSION` 与 `RUSTYTAG_PREVIOUS_VERSION` 环境变量。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// 修改项目文件之前执行，任何命令失败都会中止升级
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_bump: Vec<String>,
+    /// 创建标签之后执行
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_bump: Vec<String>,
+}
+
+/// 当前版本的来源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionSource {
+    /// 最新的版本标签，没有标签时使用项目文件中的版本
+    #[default]
+    Tags,
+    /// 项目文件中声明的版本
+    Manifest,
+    /// 标签与项目文件中较高的版本
+    Max,
+}
+
+/// 版本方案
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionScheme {
+    /// 语义化版本，通过 patch/minor/major 升级
+    #[default]
+    #[serde(rename = "semver")]
+    Semver,
+    /// 日历版本，例如 `2026.10.3`
+    #[serde(rename = "YYYY.MM.MICRO")]
+    CalverYear,
+    /// 两位年份的日历版本，例如 `26.10.0`
+    #[serde(rename = "YY.MM.MICRO")]
+    CalverShortYear,
+}
+
+impl std::fmt::Display for VersionScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Semver => "semver",
+            Self::CalverYear => "YYYY.MM.MICRO",
+            Self::CalverShortYear => "YY.MM.MICRO",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// monorepo 中独立管理版本的包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageConfig {
+    /// 包名，用于 `--package` 参数和默认标签前缀
+    pub name: String,
+    /// 包所在目录，相对于仓库根目录
+    pub path: String,
+    /// 标签前缀，例如 `web/v`，等同于 `tag_template = "web/v{version}"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_prefix: Option<String>,
+    /// 标签模板，例如 `{package}/v{version}`，优先于 `tag_prefix`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_template: Option<String>,
+}
+
+impl PackageConfig {
+    /// 按名称查找配置中的包
+    pub fn find(name: &str) -> Result<Self> {
+        LocalConfig::load()?
+            .packages
+            .into_iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Package {} is not configured", name))
+    }
+
+    /// `path` 是否位于该包的目录中
+    pub fn contains(&self, path: &Path) -> bool {
+        let normalize = |path: &Path| -> PathBuf {
+            path.components()
+                .filter(|c| !matches!(c, std::path::Component::CurDir))
+                .collect()
+        };
+        normalize(path).starts_with(normalize(Path::new(&self.path)))
+    }
+}
+
+/// 递归查找项目文件的配置，适用于 monorepo
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscoveryConfig {
+    /// 是否递归查找子目录
+    #[serde(default)]
+    pub recursive: bool,
+    /// 只包含匹配这些 glob 的文件（为空时包含全部）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// 排除匹配这些 glob 的文件
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+/// 自定义的文件版本替换规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileReplacer {
+    /// 文件路径，支持 glob
+    pub path: String,
+    /// 查找的正则表达式，支持 `{{version}}` 与 `{{previous}}` 占位符
+    pub search: String,
+    /// 替换内容，支持 `{{version}}`、`{{previous}}` 占位符与 `$1` 捕获组
+    pub replace: String,
 }
 
 impl GlobalConfig {
-    fn config_path() -> PathBuf {
+    pub fn config_path() -> PathBuf {
+        Self::config_dir().join("config.toml")
+    }
+
+    pub(crate) fn config_dir() -> PathBuf {
         dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("~"))
-            .join(".rustytag/config.json")
+            .join(".rustytag")
+    }
+
+    /// 实际读取的配置文件，只有旧版的 config.json 时返回它
+    pub fn loaded_path() -> PathBuf {
+        let legacy = Self::config_dir().join("config.json");
+        if !Self::config_path().exists() && legacy.exists() {
+            return legacy;
+        }
+        Self::config_path()
     }
 
+    /// 读取配置文件，不访问钥匙串
+    ///
+    /// 普通配置项的解析都走这里，避免每次读取配置都查询钥匙串（可能弹出解锁提示）。
     pub fn load() -> Result<Self> {
-        let path = Self::config_path();
-        if !path.exists() {
-            return Ok(Self::default());
+        let path = Self::loaded_path();
+        let config = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            let config: Result<Self> = if path.extension().is_some_and(|ext| ext == "json") {
+                serde_json::from_str(&content).map_err(anyhow::Error::from)
+            } else {
+                toml_edit::de::from_str(&content).map_err(anyhow::Error::from)
+            };
+            config.with_context(|| format!("Invalid configuration in {}", path.display()))?
+        } else {
+            Self::default()
+        };
+        Ok(config)
+    }
+
+    /// 读取配置文件，并从钥匙串补全默认 token 与各主机的 token
+    pub fn load_with_secrets() -> Result<Self> {
+        let mut config = Self::load()?;
+        if config.github_token.is_none() {
+            if let Some((token, store)) = secret::load_token(secret::DEFAULT_ACCOUNT)? {
+                config.github_token = Some(token);
+                config.token_store = Some(store);
+            }
         }
-        let content = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content)?)
+        for (host, profile) in &mut config.hosts {
+            if profile.token.is_none() {
+                if let Some((token, store)) = secret::load_token(host)? {
+                    profile.token = Some(token);
+                    profile.token_store = Some(store);
+                }
+            }
+        }
+        Ok(config)
     }
 
+    /// 保存配置，token 写入钥匙串而不是配置文件
     pub fn save(&self) -> Result<()> {
         let path = Self::config_path();
-        fs::create_dir_all(path.parent().unwrap())?;
-        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        let legacy = Self::loaded_path();
+        fs::create_dir_all(Self::config_dir())?;
+        if let Some(token) = &self.github_token {
+            secret::store_token(secret::DEFAULT_ACCOUNT, token)?;
+        }
+        for (host, profile) in &self.hosts {
+            if let Some(token) = &profile.token {
+                secret::store_token(host, token)?;
+            }
+        }
+
+        let mut config = serde_json::to_value(self)?;
+        if let Some(config) = config.as_object_mut() {
+            config.remove("github_token");
+            for profile in config
+                .get_mut("hosts")
+                .and_then(|hosts| hosts.as_object_mut())
+                .into_iter()
+                .flat_map(|hosts| hosts.values_mut())
+            {
+                if let Some(profile) = profile.as_object_mut() {
+                    profile.remove("token");
+                }
+            }
+        }
+        write_toml(&path, &[], &config)?;
+
+        // 旧版 config.json 的内容已经写入 config.toml，删除其中的明文 token
+        if legacy != path && legacy.exists() {
+            fs::remove_file(&legacy)?;
+            println!("🔐 Migrated {} to {}", legacy.display(), path.display());
+        }
         Ok(())
     }
 }
 
 impl LocalConfig {
-    fn config_path() -> PathBuf {
-        PathBuf::from(".rustytag.json")
+    /// 读取合并了所有配置层之后的配置
+    pub fn load() -> Result<Self> {
+        let source = Self::load_file()?.source;
+        let mut config: Self = serde_json::from_value(super::layers::resolve()?)
+            .map_err(|e| anyhow::anyhow!("Invalid configuration: {}", e))?;
+        config.source = source;
+        Ok(config)
     }
 
-    pub fn load() -> Result<Self> {
-        let path = Self::config_path();
-        if !path.exists() {
-            return Ok(Self::default());
+    /// 只读取项目配置文件，用于修改后保存
+    pub fn load_file() -> Result<Self> {
+        for source in ConfigSource::ALL {
+            let path = source.path();
+            if !path.exists() {
+                continue;
+            }
+            let content = fs::read_to_string(path)?;
+            let mut config: Self = if source == ConfigSource::LegacyJson {
+                serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse {}", path.display()))?
+            } else {
+                let doc = content
+                    .parse::<Document>()
+                    .with_context(|| format!("Failed to parse {}", path.display()))?;
+                let Some(table) = toml_table(doc.as_item(), source.table()) else {
+                    continue;
+                };
+                toml_edit::de::from_document(Document::from(table.clone()))
+                    .with_context(|| format!("Invalid configuration in {}", source))?
+            };
+            config.source = source;
+            return Ok(config);
         }
-        let content = fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&content)?)
+        Ok(Self::default())
     }
 
     pub fn save(&self) -> Result<()> {
-        let path = Self::config_path();
-        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        let path = self.source.path();
+        if self.source == ConfigSource::LegacyJson {
+            let mut json = serde_json::to_value(self)?;
+            // 旧版本把版本号写在 .rustytag.json 中，它不属于配置，需要保留
+            let previous: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+            if let (Some(version), Some(json)) = (previous.get("version"), json.as_object_mut()) {
+                json.insert("version".to_string(), version.clone());
+            }
+            fs::write(path, serde_json::to_string_pretty(&json)?)?;
+            return Ok(());
+        }
+        write_toml(path, self.source.table(), self)
+    }
+
+    /// CHANGELOG 路径，关闭 CHANGELOG 时返回 `None`
+    pub fn changelog_path(&self) -> Option<PathBuf> {
+        let changelog = self.changelog.clone().unwrap_or_default();
+        if !changelog.enabled.unwrap_or(true) {
+            return None;
+        }
+        Some(PathBuf::from(
+            changelog.path.unwrap_or_else(|| "CHANGELOG.md".to_string()),
+        ))
+    }
+}
+
+/// 把配置写入 TOML 文件中 `table_path` 对应的表
+///
+/// 只替换变化的键，保留其余键的格式与注释。
+fn write_toml(path: &Path, table_path: &[&str], config: &impl Serialize) -> Result<()> {
+    let mut doc = match fs::read_to_string(path) {
+        Ok(content) => content.parse::<Document>()?,
+        Err(_) => Document::new(),
+    };
+    let mut table = doc.as_table_mut();
+    for key in table_path {
+        table = table
+            .entry(key)
+            .or_insert_with(|| {
+                let mut table = Table::new();
+                table.set_implicit(true);
+                Item::Table(table)
+            })
+            .as_table_mut()
+            .ok_or_else(|| anyhow::anyhow!("{} in {} is not a table", key, path.display()))?;
+    }
+
+    let updated = toml_edit::ser::to_document(config)?;
+    let removed: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !updated.contains_key(key))
+        .collect();
+    for key in removed {
+        table.remove(&key);
+    }
+    for (key, item) in updated.iter() {
+        if table.get(key).and_then(toml_value) != toml_value(item) {
+            table.insert(key, expand_tables(item.clone()));
+        }
+    }
+    fs::write(path, doc.to_string())?;
+    Ok(())
+}
+
+/// TOML 节点的值，用于忽略格式比较两个节点
+fn toml_value(item: &Item) -> Option<serde_json::Value> {
+    let mut table = Table::new();
+    table.insert("value", item.clone());
+    toml_edit::de::from_document::<serde_json::Value>(Document::from(table))
+        .ok()
+        .map(|mut value| value["value"].take())
+}
+
+/// 将内联表写成 `[table]`，内联表数组写成 `[[table]]`
+///
+/// 只包含子表的表（例如 `hosts`）不单独输出表头，只输出 `[hosts."name"]`。
+fn expand_tables(item: Item) -> Item {
+    match item {
+        Item::Value(toml_edit::Value::InlineTable(table)) => {
+            let mut table = table.into_table();
+            table.decor_mut().set_prefix("\n");
+            let keys: Vec<String> = table.iter().map(|(key, _)| key.to_string()).collect();
+            for key in keys {
+                if let Some(child) = table.get_mut(&key).filter(|child| child.is_inline_table()) {
+                    *child = expand_tables(std::mem::take(child));
+                }
+            }
+            if !table.is_empty() && table.iter().all(|(_, child)| child.is_table()) {
+                table.set_implicit(true);
+            }
+            Item::Table(table)
+        }
+        Item::Value(toml_edit::Value::Array(array))
+            if !array.is_empty() && array.iter().all(|v| v.is_inline_table()) =>
+        {
+            let mut tables = toml_edit::ArrayOfTables::new();
+            for value in array {
+                if let toml_edit::Value::InlineTable(table) = value {
+                    let mut table = table.into_table();
+                    table.decor_mut().set_prefix("\n");
+                    tables.push(table);
+                }
+            }
+            Item::ArrayOfTables(tables)
+        }
+        item => item,
+    }
+}
+
+/// 按键路径查找 TOML 表
+fn toml_table<'a>(item: &'a Item, path: &[&str]) -> Option<&'a Table> {
+    path.iter()
+        .try_fold(item, |item, key| item.get(key))?
+        .as_table()
+}
+
+/// `config` 的子命令
+#[derive(Debug, Clone, clap::Subcommand)]
+pub enum ConfigAction {
+    /// Print the value of a configuration key
+    Get { key: String },
+    /// Set a configuration key (KEY VALUE or KEY=VALUE)
+    Set { key: String, value: Option<String> },
+    /// Remove a configuration key
+    Unset { key: String },
+    /// List all configured values
+    List {
+        /// Show the file each value comes from
+        #[arg(long)]
+        show_origin: bool,
+    },
+    /// Open the configuration file in $EDITOR
+    Edit,
+    /// Show the value of a key in every layer and which one wins
+    Explain { key: String },
+}
+
+/// 配置项的值类型
+#[derive(Debug, Clone, Copy)]
+pub enum ValueKind {
+    String,
+    Bool,
+    /// 逗号分隔的字符串列表
+    List,
+    /// 只能取给定值之一
+    Choice(&'static [&'static str]),
+}
+
+/// 可以通过 `config` 命令读写的配置项
+#[derive(Debug)]
+pub struct ConfigKey {
+    /// 配置项名称，`.` 分隔嵌套的表
+    pub name: &'static str,
+    pub kind: ValueKind,
+    /// 是否只能保存在全局配置中
+    pub global: bool,
+    /// 是否为需要隐藏的敏感信息
+    pub secret: bool,
+    /// 未设置时的默认值
+    pub default: Option<&'static str>,
+}
+
+impl ConfigKey {
+    const fn new(name: &'static str, kind: ValueKind) -> Self {
+        Self {
+            name,
+            kind,
+            global: false,
+            secret: false,
+            default: None,
+        }
+    }
+
+    const fn with_default(mut self, default: &'static str) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// 覆盖该配置项的环境变量，例如 `RUSTYTAG_CHANGELOG_PATH`
+    pub fn env_var(&self) -> String {
+        format!("RUSTYTAG_{}", self.name.replace('.', "_").to_uppercase())
+    }
+
+    /// 按名称查找配置项，兼容 `GITHUB_TOKEN` 这类大写写法
+    pub fn find(name: &str) -> Result<&'static Self> {
+        Self::find_in(CONFIG_KEYS, name)
+    }
+
+    fn find_in(keys: &'static [ConfigKey], name: &str) -> Result<&'static Self> {
+        let normalized = name.to_lowercase();
+        keys.iter()
+            .find(|key| key.name == normalized)
+            .ok_or_else(|| {
+                let known: Vec<_> = keys.iter().map(|key| key.name).collect();
+                anyhow::anyhow!(
+                    "Unknown configuration key {} (known keys: {})",
+                    name,
+                    known.join(", ")
+                )
+            })
+    }
+
+    fn path(&self) -> Vec<&'static str> {
+        self.name.split('.').collect()
+    }
+
+    /// 在 JSON 形式的配置中读取该配置项
+    pub(crate) fn lookup<'a>(
+        &self,
+        config: &'a serde_json::Value,
+    ) -> Option<&'a serde_json::Value> {
+        self.path()
+            .iter()
+            .try_fold(config, |value, part| value.get(part))
+            .filter(|value| !value.is_null())
+    }
+
+    /// 在 JSON 形式的配置中写入或删除（`None`）该配置项
+    pub(crate) fn write(
+        &self,
+        config: &mut serde_json::Value,
+        new_value: Option<serde_json::Value>,
+    ) -> Result<()> {
+        let path = self.path();
+        let (last, parents) = path.split_last().expect("configuration key is not empty");
+        let mut table = &mut *config;
+        for part in parents {
+            if new_value.is_none() && table.get(part).is_none() {
+                return Ok(());
+            }
+            let object = table
+                .as_object_mut()
+                .ok_or_else(|| anyhow::anyhow!("{} is not a table", part))?;
+            table = object
+                .entry(part.to_string())
+                .or_insert_with(|| serde_json::json!({}));
+            if table.is_null() {
+                *table = serde_json::json!({});
+            }
+        }
+        let object = table
+            .as_object_mut()
+            .ok_or_else(|| anyhow::anyhow!("{} is not a table", self.name))?;
+        match new_value {
+            Some(value) => {
+                object.insert(last.to_string(), value);
+            }
+            None => {
+                object.remove(*last);
+                // 删除后不再保留空的表
+                if let (Some(parent), Some(root)) = (parents.first(), config.as_object_mut()) {
+                    if root
+                        .get(*parent)
+                        .and_then(|v| v.as_object())
+                        .is_some_and(|v| v.is_empty())
+                    {
+                        root.remove(*parent);
+                    }
+                }
+            }
+        }
         Ok(())
     }
+
+    /// 按类型解析命令行或环境变量中的值
+    pub(crate) fn parse(&self, raw: &str) -> Result<serde_json::Value> {
+        match self.kind {
+            ValueKind::String => Ok(raw.into()),
+            ValueKind::Bool => match raw {
+                "true" | "yes" | "on" | "1" => Ok(true.into()),
+                "false" | "no" | "off" | "0" => Ok(false.into()),
+                _ => Err(anyhow::anyhow!(
+                    "{} expects true or false, got {}",
+                    self.name,
+                    raw
+                )),
+            },
+            ValueKind::List => Ok(raw
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .collect::<Vec<_>>()
+                .into()),
+            ValueKind::Choice(choices) if choices.contains(&raw) => Ok(raw.into()),
+            ValueKind::Choice(choices) => Err(anyhow::anyhow!(
+                "{} expects one of {}, got {}",
+                self.name,
+                choices.join(", "),
+                raw
+            )),
+        }
+    }
+}
+
+/// 所有可以通过 `config` 命令读写的配置项
+pub const CONFIG_KEYS: &[ConfigKey] = &[
+    ConfigKey {
+        global: true,
+        secret: true,
+        ..ConfigKey::new("github_token", ValueKind::String)
+    },
+    ConfigKey {
+        global: true,
+        ..ConfigKey::new("github_token_file", ValueKind::String)
+    },
+    ConfigKey {
+        global: true,
+        ..ConfigKey::new("github_token_command", ValueKind::String)
+    },
+    ConfigKey::new("version_prefix", ValueKind::String),
+    ConfigKey::new("tag_template", ValueKind::String),
+    ConfigKey::new("package_tag_template", ValueKind::String),
+    ConfigKey::new(
+        "version_source",
+        ValueKind::Choice(&["tags", "manifest", "max"]),
+    )
+    .with_default("tags"),
+    ConfigKey::new(
+        "version_scheme",
+        ValueKind::Choice(&["semver", "YYYY.MM.MICRO", "YY.MM.MICRO"]),
+    )
+    .with_default("semver"),
+    ConfigKey::new("branches", ValueKind::List),
+    ConfigKey::new("changelog.enabled", ValueKind::Bool).with_default("true"),
+    ConfigKey::new("changelog.path", ValueKind::String).with_default("CHANGELOG.md"),
+    ConfigKey::new("hooks.pre_bump", ValueKind::List),
+    ConfigKey::new("hooks.post_bump", ValueKind::List),
+    ConfigKey::new("helm_app_version", ValueKind::Bool),
+    ConfigKey::new("discovery.recursive", ValueKind::Bool),
+    ConfigKey::new("discovery.include", ValueKind::List),
+    ConfigKey::new("discovery.exclude", ValueKind::List),
+];
+
+/// `config --host` 可以读写的主机配置项
+pub const HOST_KEYS: &[ConfigKey] = &[
+    ConfigKey {
+        secret: true,
+        ..ConfigKey::new("token", ValueKind::String)
+    },
+    ConfigKey::new("token_file", ValueKind::String),
+    ConfigKey::new("token_command", ValueKind::String),
+    ConfigKey::new("api_url", ValueKind::String),
+    ConfigKey::new("provider", ValueKind::Choice(&["github", "gitlab"])),
+];
+
+/// 以 JSON 形式读写配置，修改后重新反序列化以校验整体结构
+struct ConfigDocument {
+    global: bool,
+    source: ConfigSource,
+    value: serde_json::Value,
 }
 
-pub fn handle_config_command(set: Option<String>, global: bool, local: bool) -> Result<()> {
-    if let Some(set_str) = set {
-        let parts: Vec<&str> = set_str.split('=').collect();
-        if parts.len() != 2 {
-            return Err(anyhow::anyhow!("Invalid format. Use KEY=VALUE"));
+impl ConfigDocument {
+    fn load(global: bool) -> Result<Self> {
+        if global {
+            return Ok(Self {
+                global,
+                source: ConfigSource::default(),
+                value: serde_json::to_value(GlobalConfig::load_with_secrets()?)?,
+            });
         }
+        let config = LocalConfig::load_file()?;
+        Ok(Self {
+            global,
+            source: config.source,
+            value: serde_json::to_value(&config)?,
+        })
+    }
 
-        // 如果没有指定 local 或 global，根据配置类型自动选择
-        let (is_global, is_local) = if !global && !local {
-            match parts[0] {
-                "GITHUB_TOKEN" => (true, false),
-                "VERSION_PREFIX" => (false, true),
-                _ => return Err(anyhow::anyhow!("Unknown configuration key")),
+    fn origin(&self, key: &ConfigKey) -> Result<String> {
+        if self.global && key.secret {
+            if let Some((_, store)) = secret::load_token(secret::DEFAULT_ACCOUNT)? {
+                return Ok(store.to_string());
             }
+        }
+        Ok(if self.global {
+            GlobalConfig::loaded_path().display().to_string()
+        } else {
+            self.source.to_string()
+        })
+    }
+
+    fn get(&self, key: &ConfigKey) -> Option<&serde_json::Value> {
+        key.lookup(&self.value)
+    }
+
+    fn set(&mut self, key: &ConfigKey, new_value: Option<serde_json::Value>) -> Result<()> {
+        key.write(&mut self.value, new_value)
+    }
+
+    fn save(&self) -> Result<()> {
+        if self.global {
+            serde_json::from_value::<GlobalConfig>(self.value.clone())?.save()
+        } else {
+            let mut config = serde_json::from_value::<LocalConfig>(self.value.clone())
+                .map_err(|e| anyhow::anyhow!("Invalid configuration: {}", e))?;
+            config.source = self.source;
+            config.save()
+        }
+    }
+}
+
+/// 显示用的配置值，敏感信息只保留首尾几位
+fn display_value(key: &ConfigKey, value: &serde_json::Value) -> String {
+    let text = match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_str().map(String::from).unwrap_or(item.to_string()))
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    };
+    if key.secret {
+        mask_secret(&text)
+    } else {
+        text
+    }
+}
+
+/// 隐藏敏感信息，过短的值完全隐藏
+pub fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() < 12 {
+        return "*".repeat(chars.len().max(4));
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}...{}", head, tail)
+}
+
+/// 确定配置项所在的作用域，未指定时项目配置项写入项目配置
+fn resolve_scope(key: &ConfigKey, global: bool, local: bool) -> Result<bool> {
+    match (key.global, global, local) {
+        (true, _, true) => Err(anyhow::anyhow!("{} can only be set globally", key.name)),
+        (is_global, global, _) => Ok(is_global || global),
+    }
+}
+
+fn set_config_value(key: &str, value: &str, global: bool, local: bool) -> Result<()> {
+    let key = ConfigKey::find(key)?;
+    let mut document = ConfigDocument::load(resolve_scope(key, global, local)?)?;
+    document.set(key, Some(key.parse(value)?))?;
+    document.save()?;
+    println!("✔ Set {} in {}", key.name, document.origin(key)?);
+    Ok(())
+}
+
+fn list_config(show_origin: bool) -> Result<()> {
+    println!("\n⚙️  Current Configuration");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    for key in CONFIG_KEYS {
+        let Some((value, layer)) = resolve_key(key)? else {
+            continue;
+        };
+        if layer.layer == Layer::Default {
+            continue;
+        }
+        if show_origin {
+            println!(
+                "  {}={}  ({}: {})",
+                key.name,
+                display_value(key, &value),
+                layer.layer,
+                key_origin(key, &layer)
+            );
         } else {
-            (global, local)
+            println!("  {}={}", key.name, display_value(key, &value));
+        }
+    }
+    for (host, profile) in GlobalConfig::load_with_secrets()?.hosts {
+        print_host_profile(&host, &profile, show_origin)?;
+    }
+    println!();
+    Ok(())
+}
+
+/// 列出主机配置中已设置的项
+fn print_host_profile(host: &str, profile: &HostConfig, show_origin: bool) -> Result<()> {
+    let value = serde_json::to_value(profile)?;
+    for key in HOST_KEYS {
+        let Some(item) = key.lookup(&value) else {
+            continue;
         };
+        let name = format!("hosts.\"{}\".{}", host, key.name);
+        if show_origin {
+            let origin = match (&profile.token_store, key.secret) {
+                (Some(store), true) => store.to_string(),
+                _ => GlobalConfig::config_path().display().to_string(),
+            };
+            println!(
+                "  {}={}  ({}: {})",
+                name,
+                display_value(key, item),
+                Layer::Global,
+                origin
+            );
+        } else {
+            println!("  {}={}", name, display_value(key, item));
+        }
+    }
+    Ok(())
+}
 
-        match (parts[0], is_global, is_local) {
-            ("GITHUB_TOKEN", true, _) => {
-                let mut config = GlobalConfig::load()?;
-                config.github_token = Some(parts[1].to_string());
-                config.save()?;
-                println!("✔ Global configuration saved");
+/// 读写 `[hosts."<host>"]` 中的凭据，主机配置只保存在全局配置中
+fn handle_host_command(host: &str, action: ConfigAction) -> Result<()> {
+    let host = host.trim().to_lowercase();
+    let mut config = GlobalConfig::load_with_secrets()?;
+    let profile = config.hosts.get(&host).cloned().unwrap_or_default();
+    let mut value = serde_json::to_value(&profile)?;
+
+    match action {
+        ConfigAction::Get { key } => {
+            let key = ConfigKey::find_in(HOST_KEYS, &key)?;
+            let value = key
+                .lookup(&value)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("{} is not set for {}", key.name, host))?;
+            println!("{}", value);
+            return Ok(());
+        }
+        ConfigAction::Set { key, value: raw } => {
+            let (key, raw) = split_assignment(key, raw)?;
+            let key = ConfigKey::find_in(HOST_KEYS, &key)?;
+            key.write(&mut value, Some(key.parse(&raw)?))?;
+            save_host_profile(&mut config, &host, value)?;
+            let origin = match secret::load_token(&host)? {
+                Some((_, store)) if key.secret => store.to_string(),
+                _ => GlobalConfig::config_path().display().to_string(),
+            };
+            println!("✔ Set {} for {} in {}", key.name, host, origin);
+        }
+        ConfigAction::Unset { key } => {
+            let key = ConfigKey::find_in(HOST_KEYS, &key)?;
+            if key.lookup(&value).is_none() {
+                println!("ℹ️  {} is not set for {}", key.name, host);
+                return Ok(());
             }
-            ("VERSION_PREFIX", _, true) => {
-                let mut config = LocalConfig::load()?;
-                config.version_prefix = Some(parts[1].to_string());
-                config.save()?;
-                println!("✔ Local configuration saved");
+            key.write(&mut value, None)?;
+            save_host_profile(&mut config, &host, value)?;
+            if key.secret {
+                secret::delete_token(&host)?;
             }
-            _ => {
-                return Err(anyhow::anyhow!(
-                "Invalid configuration: GITHUB_TOKEN must be global, VERSION_PREFIX must be local"
-            ))
+            println!("✔ Unset {} for {}", key.name, host);
+        }
+        ConfigAction::List { show_origin } => {
+            println!("\n🌐 {}", host);
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            print_host_profile(&host, &profile, show_origin)?;
+            let provider = HostConfig::provider(&host)?;
+            println!("  provider={}  (effective)", provider);
+            // API 地址只对 GitHub 主机有意义
+            if provider == Provider::Github {
+                println!("  api_url={}  (effective)", HostConfig::api_url(&host)?);
             }
+            println!();
+        }
+        ConfigAction::Edit => edit_config(true)?,
+        ConfigAction::Explain { .. } => {
+            return Err(anyhow::anyhow!("config explain does not support --host"))
         }
+    }
+    Ok(())
+}
+
+/// 写回主机配置，全部清空时删除该主机
+fn save_host_profile(
+    config: &mut GlobalConfig,
+    host: &str,
+    value: serde_json::Value,
+) -> Result<()> {
+    let profile: HostConfig = serde_json::from_value(value)?;
+    let empty = serde_json::to_value(&profile)?
+        .as_object()
+        .is_some_and(|o| o.is_empty());
+    if empty {
+        config.hosts.remove(host);
     } else {
-        // 显示当前配置
-        println!("\n⚙️  Current Configuration");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        config.hosts.insert(host.to_string(), profile);
+    }
+    config.save()
+}
 
-        // 显示全局配置
-        println!("🌍 Global Configuration:");
-        let global_config = GlobalConfig::load()?;
-        println!(
-            "  🔑 GITHUB_TOKEN: {}",
-            global_config
-                .github_token
-                .as_ref()
-                .map(|t| format!("{}...{}", &t[..6], &t[t.len() - 4..]))
-                .unwrap_or_else(|| "Not set".to_string())
-        );
+/// 解析 `KEY VALUE` 或 `KEY=VALUE`，只在第一个 `=` 处分割，值中可以包含 `=`
+fn split_assignment(key: String, value: Option<String>) -> Result<(String, String)> {
+    match value {
+        Some(value) => Ok((key, value)),
+        None => key
+            .split_once('=')
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .ok_or_else(|| anyhow::anyhow!("Invalid format. Use KEY=VALUE")),
+    }
+}
 
-        // 显示本地配置
-        println!("\n📍 Local Configuration:");
-        let local_config = LocalConfig::load()?;
+/// 显示配置项在每一层中的值以及最终生效的层
+fn explain_config(key: &str) -> Result<()> {
+    let key = ConfigKey::find(key)?;
+    println!("\n🔎 {}", key.name);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    let mut winner = None;
+    for layer in load_layers(key.secret)? {
+        let origin = key_origin(key, &layer);
+        let value = key.lookup(&layer.config);
         println!(
-            "  📌 VERSION_PREFIX: {}",
-            local_config.version_prefix.as_deref().unwrap_or("Not set")
+            "  {:<8} {:<40} {}",
+            layer.layer.to_string(),
+            origin,
+            value
+                .map(|v| display_value(key, v))
+                .unwrap_or_else(|| "(not set)".to_string())
         );
-        println!();
+        if let Some(value) = value {
+            winner = Some((display_value(key, value), layer.layer, origin));
+        }
+    }
+    match winner {
+        Some((value, layer, origin)) => {
+            println!(
+                "\n✔ {} = {} (from {}: {})\n",
+                key.name, value, layer, origin
+            )
+        }
+        None => println!("\nℹ️  {} is not set\n", key.name),
+    }
+    Ok(())
+}
+
+/// 用 $VISUAL / $EDITOR 打开配置文件，保存后校验
+fn edit_config(global: bool) -> Result<()> {
+    let path = if global {
+        let path = GlobalConfig::config_path();
+        if !path.exists() {
+            GlobalConfig::default().save()?;
+        }
+        path
+    } else {
+        let source = LocalConfig::load_file()?.source;
+        if !source.path().exists() {
+            fs::write(source.path(), "")?;
+        }
+        source.path().to_path_buf()
+    };
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // 编辑器可能带参数，例如 `code --wait`
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("$EDITOR is empty"))?;
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to start editor {}", editor))?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("Editor exited with {}", status));
+    }
+
+    if global {
+        GlobalConfig::load()?;
+    } else {
+        LocalConfig::load()?;
+    }
+    println!("✔ {} is valid", path.display());
+    Ok(())
+}
+
+pub fn handle_config_command(
+    action: Option<ConfigAction>,
+    set: Option<String>,
+    global: bool,
+    local: bool,
+    host: Option<String>,
+) -> Result<()> {
+    if global && local {
+        return Err(anyhow::anyhow!(
+            "--global and --local cannot be used together"
+        ));
+    }
+    if host.is_some() && local {
+        return Err(anyhow::anyhow!("Host credentials can only be set globally"));
+    }
+    // 兼容旧的 `config --set KEY=VALUE`
+    let action = match (action, set) {
+        (Some(action), _) => action,
+        (None, Some(set)) => ConfigAction::Set {
+            key: set,
+            value: None,
+        },
+        (None, None) => ConfigAction::List { show_origin: false },
+    };
+    if let Some(host) = host {
+        return handle_host_command(&host, action);
+    }
+
+    match action {
+        ConfigAction::Get { key } => {
+            let key = ConfigKey::find(&key)?;
+            // 未指定作用域时读取合并后的值
+            let value = if global || local {
+                ConfigDocument::load(resolve_scope(key, global, local)?)?
+                    .get(key)
+                    .cloned()
+            } else {
+                resolve_key(key)?.map(|(value, _)| value)
+            };
+            let value = value
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("{} is not set", key.name))?;
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                _ => display_value(key, value),
+            };
+            println!("{}", value);
+        }
+        ConfigAction::Set { key, value } => {
+            let (key, value) = split_assignment(key, value)?;
+            set_config_value(&key, &value, global, local)?;
+        }
+        ConfigAction::Unset { key } => {
+            let key = ConfigKey::find(&key)?;
+            let mut document = ConfigDocument::load(resolve_scope(key, global, local)?)?;
+            if document.get(key).is_none() {
+                println!("ℹ️  {} is not set", key.name);
+                return Ok(());
+            }
+            let origin = document.origin(key)?;
+            document.set(key, None)?;
+            document.save()?;
+            if key.secret {
+                secret::delete_token(secret::DEFAULT_ACCOUNT)?;
+            }
+            println!("✔ Unset {} in {}", key.name, origin);
+        }
+        ConfigAction::List { show_origin } => list_config(show_origin)?,
+        ConfigAction::Edit => edit_config(global)?,
+        ConfigAction::Explain { key } => explain_config(&key)?,
     }
 
     Ok(())
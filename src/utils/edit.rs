@@ -0,0 +1,43 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 暂存的文件修改
+//
+// 一次升级会修改多个清单、锁文件和自定义规则匹配的文件。
+// 所有修改先在内存中计算，全部成功后再统一写入，
+// 任何一个文件出错都不会留下只升级了一部分的工作区。
+
+/// 待写入的文件内容，按首次修改的顺序写入
+#[derive(Debug, Clone, Default)]
+pub struct FileEdits {
+    files: Vec<(PathBuf, String)>,
+}
+
+impl FileEdits {
+    /// 读取文件内容，已暂存的文件返回修改后的内容
+    pub fn read(&self, path: &Path) -> Result<String> {
+        match self.files.iter().find(|(p, _)| p == path) {
+            Some((_, content)) => Ok(content.clone()),
+            None => fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// 暂存文件的新内容
+    pub fn write(&mut self, path: &Path, content: String) {
+        match self.files.iter_mut().find(|(p, _)| p == path) {
+            Some((_, existing)) => *existing = content,
+            None => self.files.push((path.to_path_buf(), content)),
+        }
+    }
+
+    /// 写入所有暂存的修改
+    pub fn commit(self) -> Result<()> {
+        for (path, content) in self.files {
+            fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
@@ -2,16 +2,69 @@ use anyhow::Result;
 use chrono::Local;
 use std::fs::OpenOptions;
 use std::io::Write;
+use std::path::Path;
 
+use super::config::PackageConfig;
+use super::git::GitCommit;
 use super::version::Version;
 
-pub fn create_changelog(version: &Version) -> Result<()> {
+pub fn create_changelog(version: &Version, changelog: &Path) -> Result<()> {
     println!("🔄 Generating CHANGELOG...");
+
+    // Get previous version
+    let previous_version = crate::utils::git::get_latest_tag()?;
+    if let Some(previous_version) = &previous_version {
+        println!("📌 Previous version: {}", previous_version);
+    }
+
+    // Get commit history
+    println!("🔍 Getting commit history...");
+    let commits = match &previous_version {
+        None => {
+            println!("⚠️ No previous version found, getting all commits");
+            crate::utils::git::get_git_commits()?
+        }
+        Some(previous_version) => {
+            println!("📊 Getting new commits after {}", previous_version);
+            crate::utils::git::get_commits_after_tag(&previous_version.to_string())?
+        }
+    };
+    println!("✅ Found {} commits", commits.len());
+
+    write_changelog(changelog, version, previous_version.as_ref(), &commits)
+}
+
+/// 为 monorepo 中的单个包生成 CHANGELOG，只包含修改了该包目录的提交
+pub fn create_package_changelog(
+    package: &PackageConfig,
+    version: &Version,
+    previous_version: Option<&Version>,
+) -> Result<()> {
+    println!("🔄 Generating CHANGELOG for package {}...", package.name);
+    let previous_tag = previous_version.map(|v| v.to_string());
+    let commits =
+        crate::utils::git::get_commits_touching(previous_tag.as_deref(), Path::new(&package.path))?;
+    println!("✅ Found {} commits", commits.len());
+
+    write_changelog(
+        &Path::new(&package.path).join("CHANGELOG.md"),
+        version,
+        previous_version,
+        &commits,
+    )
+}
+
+fn write_changelog(
+    path: &Path,
+    version: &Version,
+    previous_version: Option<&Version>,
+    commits: &[GitCommit],
+) -> Result<()> {
     let mut file = OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
-        .open("CHANGELOG.md")?;
+        .open(path)?;
 
     // Get current date
     let date = Local::now().format("%Y-%m-%d").to_string();
@@ -34,27 +87,21 @@ pub fn create_changelog(version: &Version) -> Result<()> {
         writeln!(file, "---")?;
     }
 
-    // Get remote repository URL and previous version
+    // Get remote repository URL
     let remote_url = crate::utils::git::get_remote_url()?;
-    let previous_version = crate::utils::git::get_latest_tag()?;
     println!("🔗 Remote repository URL: {}", remote_url);
-    println!("📌 Previous version: {}", previous_version);
 
     // Write version title and comparison link
-    let initial_version =
-        Version::new(semver::Version::new(0, 1, 0)).with_prefix(previous_version.prefix.clone());
-
-    if previous_version.version == initial_version.version {
-        writeln!(
+    match previous_version {
+        None => writeln!(
             file,
             "### [{}]({}/commits/{}) ({})",
             version,
             remote_url.trim_end_matches(".git"),
             version,
             date
-        )?;
-    } else {
-        writeln!(
+        )?,
+        Some(previous_version) => writeln!(
             file,
             "### [{}]({}/compare/{}...{}) ({})",
             version,
@@ -62,25 +109,14 @@ pub fn create_changelog(version: &Version) -> Result<()> {
             previous_version,
             version,
             date
-        )?;
+        )?,
     }
     writeln!(file)?;
 
-    // Get commit history
-    println!("🔍 Getting commit history...");
-    let commits = if previous_version.version == initial_version.version {
-        println!("⚠️ No previous version found, getting all commits");
-        crate::utils::git::get_git_commits()?
-    } else {
-        println!("📊 Getting new commits after {}", previous_version);
-        crate::utils::git::get_commits_after_tag(&previous_version.to_string())?
-    };
-    println!("✅ Found {} commits", commits.len());
-
     // Write all commits
     writeln!(file, "### Commits")?;
     writeln!(file)?;
-    for commit in &commits {
+    for commit in commits {
         // Skip "chore: release" commits
         if commit.message.starts_with("chore: release") {
             continue;
@@ -96,10 +132,42 @@ pub fn create_changelog(version: &Version) -> Result<()> {
     }
     writeln!(file)?;
 
-    println!("✨ CHANGELOG.md generated successfully");
+    println!("✨ {} generated successfully", path.display());
     Ok(())
 }
 
+/// 从 CHANGELOG 中提取指定版本的章节
+///
+/// 章节从 `### [<version>]` 标题开始，到下一个版本标题或文件结尾为止。
+/// 标签信息与 GitHub Release 均使用此结果，保证三者内容一致。
+pub fn extract_changelog_section(changelog: &str, version: &Version) -> Option<String> {
+    let heading_version = |line: &str| {
+        line.strip_prefix("### [")
+            .and_then(|rest| rest.split_once(']'))
+            .and_then(|(v, _)| Version::parse(v).ok())
+    };
+
+    let mut section: Vec<&str> = Vec::new();
+    let mut in_section = false;
+    for line in changelog.lines() {
+        if let Some(v) = heading_version(line) {
+            if in_section {
+                break;
+            }
+            in_section = v.version == version.version;
+        }
+        if in_section {
+            section.push(line);
+        }
+    }
+
+    if section.is_empty() {
+        None
+    } else {
+        Some(section.join("\n").trim().to_string())
+    }
+}
+
 pub fn create_gitignore() -> Result<()> {
     let mut gitignore = OpenOptions::new()
         .write(true)
@@ -3,10 +3,15 @@ use dirs::home_dir;
 use git2::{Remote, Repository};
 use std::fs;
 use std::io::Write;
-use std::path::Path;
-
-use super::file::create_gitignore;
-use super::project::ProjectFile;
+use std::path::{Path, PathBuf};
+
+use super::cargo::{workspace_members, workspace_root};
+use super::config::{LocalConfig, PackageConfig};
+use super::file::{create_gitignore, extract_changelog_section};
+use super::lockfile::lock_files;
+use super::project::{ProjectFile, ProjectFileType};
+use super::replace::replacer_paths;
+use super::tag::TagTemplate;
 use super::version::Version;
 
 // Git 操作相关功能模块
@@ -44,52 +49,63 @@ pub fn initialize_git_repo() -> Result<()> {
     Ok(())
 }
 
-pub fn get_latest_tag() -> Result<Version> {
+/// 获取最新的版本标签，没有可解析的版本标签时返回 `None`
+pub fn get_latest_tag() -> Result<Option<Version>> {
     let repo = Repository::open(".")?;
-    let tags = repo.tag_names(None)?;
-
-    // If no tags found, return initial version
-    if tags.is_empty() {
-        println!("⚠️ No tags found, using initial version");
-        return Ok(Version::new(semver::Version::new(0, 1, 0)));
-    }
+    Ok(version_tags(&repo)?
+        .into_iter()
+        .max_by(|a, b| a.version.cmp(&b.version)))
+}
 
-    // Collect all tags and parse to Version
-    let mut versions: Vec<_> = tags
+/// 所有根目录版本标签，排除 monorepo 中的包标签
+///
+/// 配置了标签模板时只保留符合模板的标签，否则第一个数字之前的部分视为前缀。
+fn version_tags(repo: &Repository) -> Result<Vec<Version>> {
+    let config = LocalConfig::load()?;
+    let template = TagTemplate::for_root(&config)?;
+    let packages = TagTemplate::for_packages(&config)?;
+    Ok(get_local_tags(repo)?
         .iter()
-        .flatten()
-        .filter_map(|t| Version::parse(t).ok())
-        .collect();
+        .filter(|tag| !packages.iter().any(|p| p.match_tag(tag).is_some()))
+        .filter_map(|tag| match &template {
+            Some(template) => template.match_tag(tag),
+            None => Version::parse(tag).ok(),
+        })
+        .collect())
+}
 
-    // Sort by version number in descending order
-    versions.sort_by(|a, b| b.version.cmp(&a.version));
-
-    // Get latest version
-    let latest_version = versions.first().cloned().ok_or_else(|| {
-        println!("⚠️ No valid version tags found, using initial version");
-        anyhow::anyhow!("No valid version tags found")
-    })?;
-
-    // If prefix exists and not configured, save to config
-    if !latest_version.prefix.is_empty() {
-        let mut config = super::config::LocalConfig::load()?;
-        if config.version_prefix.is_none() {
-            config.version_prefix = Some(latest_version.prefix.clone());
-            config.save()?;
-            println!(
-                "✨ Version prefix auto-configured: {}",
-                latest_version.prefix
-            );
+/// 检查当前分支是否允许升级版本，`branches` 为空时不限制
+pub fn ensure_release_branch(repo: &Repository, branches: &[String]) -> Result<()> {
+    if branches.is_empty() {
+        return Ok(());
+    }
+    let head = repo.head()?;
+    let branch = head
+        .shorthand()
+        .filter(|_| head.is_branch())
+        .ok_or_else(|| anyhow::anyhow!("HEAD is detached, releases are limited to branches"))?;
+    for pattern in branches {
+        if globset::Glob::new(pattern)?
+            .compile_matcher()
+            .is_match(branch)
+        {
+            return Ok(());
         }
     }
-
-    Ok(latest_version)
+    Err(anyhow::anyhow!(
+        "Branch {} is not a release branch (allowed: {})",
+        branch,
+        branches.join(", ")
+    ))
 }
 
-pub fn commit_changes(repo: &Repository, version: &Version) -> Result<()> {
+/// 提交版本变更，`release` 为提交信息中的版本名，`changelog` 为本次生成的 CHANGELOG（未生成时为 `None`）
+pub fn commit_changes(repo: &Repository, release: &str, changelog: Option<&Path>) -> Result<()> {
     let mut index = repo.index()?;
-    index.add_path(Path::new("CHANGELOG.md"))?;
-    index.write()?;
+    if let Some(changelog) = changelog {
+        index.add_path(changelog)?;
+        index.write()?;
+    }
 
     let oid = index.write_tree()?;
     let tree = repo.find_tree(oid)?;
@@ -99,7 +115,7 @@ pub fn commit_changes(repo: &Repository, version: &Version) -> Result<()> {
         Some("HEAD"),
         &signature,
         &signature,
-        &format!("chore: release {}", version.version),
+        &format!("chore: release {}", release),
         &tree,
         &[&parent_commit],
     )?;
@@ -113,6 +129,7 @@ pub fn commit_changes(repo: &Repository, version: &Version) -> Result<()> {
 ///
 /// * `repo` - Git 仓库引用
 /// * `version` - 版本号
+/// * `changelog` - 用作标签信息的 CHANGELOG 文件，为 `None` 时使用默认信息
 ///
 /// # 示例
 ///
@@ -120,24 +137,26 @@ pub fn commit_changes(repo: &Repository, version: &Version) -> Result<()> {
 /// use git2::Repository;
 /// use rustytag::utils::version::Version;
 /// use rustytag::utils::git::create_tag;
+/// use std::path::Path;
 ///
 /// # fn main() -> anyhow::Result<()> {
 /// let repo = Repository::open(".")?;
 /// let version = Version::new(semver::Version::new(1, 0, 0));
-/// create_tag(&repo, &version)?;
+/// create_tag(&repo, &version, Some(Path::new("CHANGELOG.md")))?;
 /// # Ok(())
 /// # }
 /// ```
-pub fn create_tag(repo: &Repository, version: &Version) -> Result<()> {
+pub fn create_tag(repo: &Repository, version: &Version, changelog: Option<&Path>) -> Result<()> {
     let obj = repo.head()?.peel_to_commit()?.into_object();
     let signature = repo.signature()?;
-    let changelog = std::fs::read_to_string("CHANGELOG.md")?;
-    let version_content = changelog
-        .split("---\n")
-        .nth(1)
-        .unwrap_or("No changelog content")
-        .trim()
-        .to_string();
+    let version_content = match changelog {
+        Some(changelog) => {
+            let changelog = std::fs::read_to_string(changelog)?;
+            extract_changelog_section(&changelog, version)
+                .unwrap_or_else(|| "No changelog content".to_string())
+        }
+        None => format!("Release {}", version),
+    };
     repo.tag(
         &version.to_string(),
         &obj,
@@ -149,7 +168,18 @@ pub fn create_tag(repo: &Repository, version: &Version) -> Result<()> {
     Ok(())
 }
 
-pub fn get_remote(repo: &Repository) -> Result<Remote> {
+/// 读取附注标签的信息
+///
+/// 轻量标签没有附带信息，此时返回错误。
+pub fn get_tag_message(repo: &Repository, tag: &str) -> Result<String> {
+    let reference = repo.find_reference(&format!("refs/tags/{}", tag))?;
+    let tag_obj = reference
+        .peel_to_tag()
+        .with_context(|| format!("Tag {} is not an annotated tag", tag))?;
+    Ok(tag_obj.message().unwrap_or("").to_string())
+}
+
+pub fn get_remote(repo: &Repository) -> Result<Remote<'_>> {
     match repo.find_remote("origin") {
         Ok(remote) => Ok(remote),
         Err(e) if e.code() == git2::ErrorCode::NotFound => {
@@ -288,6 +318,19 @@ pub fn get_remote_url() -> Result<String> {
     Ok(convert_ssh_to_https(url))
 }
 
+/// 远程地址中的主机名（小写，不含用户名和端口）
+///
+/// 支持 `https://host/owner/repo`、`ssh://git@host:22/owner/repo` 与 `git@host:owner/repo`。
+pub fn remote_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split('/').next()?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = host.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
 fn convert_ssh_to_https(url: &str) -> String {
     if url.starts_with("git@") {
         // Convert git@github.com:user/repo.git to https://github.com/user/repo
@@ -308,7 +351,7 @@ pub struct ProjectInfo {
 }
 
 pub fn get_project_info(repo: &Repository) -> Result<ProjectInfo> {
-    let version = get_latest_tag().unwrap_or_else(|_| Version::new(semver::Version::new(0, 1, 0)));
+    let version = crate::utils::version::get_latest_version()?;
 
     let repo_url = get_remote_url().ok();
     let commits = get_git_commits()?;
@@ -324,11 +367,50 @@ pub fn get_project_info(repo: &Repository) -> Result<ProjectInfo> {
 }
 
 pub fn add_project_files(repo: &Repository) -> Result<()> {
+    // 自定义替换规则涉及的文件
+    let mut extra = Vec::new();
+    for replacer in LocalConfig::load()?.files {
+        extra.extend(replacer_paths(&replacer)?);
+    }
+    stage_files(repo, ProjectFile::detect_all()?, extra)
+}
+
+/// 暂存 monorepo 中单个包的项目文件
+pub fn add_package_files(repo: &Repository, package: &PackageConfig) -> Result<()> {
+    let files = ProjectFile::detect_in(Path::new(&package.path))?;
+    stage_files(repo, files, Vec::new())
+}
+
+fn stage_files(repo: &Repository, files: Vec<ProjectFile>, mut paths: Vec<PathBuf>) -> Result<()> {
     let mut index = repo.index()?;
-    let project_files = ProjectFile::detect_all()?;
-    for file in project_files {
-        if let Err(e) = index.add_path(&file.path) {
-            println!("⚠️ Cannot add file {:?}: {}", file.path, e);
+    for file in files {
+        // Cargo 工作区成员；单独升级的成员还会修改根目录的清单、Cargo.lock 与其他成员
+        if let ProjectFileType::CargoToml = file.file_type {
+            paths.extend(workspace_members(
+                file.path.parent().unwrap_or(Path::new("")),
+            )?);
+            if let Some(root) = workspace_root(&file.path)? {
+                paths.push(root.join("Cargo.toml"));
+                paths.extend(workspace_members(&root)?);
+                let lock = root.join("Cargo.lock");
+                if index.get_path(&lock, 0).is_some() {
+                    paths.push(lock);
+                }
+            }
+        }
+        // 只暂存已纳入版本控制的锁文件
+        paths.extend(
+            lock_files(&file)
+                .into_iter()
+                .filter(|lock| index.get_path(lock, 0).is_some()),
+        );
+        paths.push(file.path);
+    }
+
+    for path in paths {
+        let path = path.strip_prefix("./").unwrap_or(&path).to_path_buf();
+        if let Err(e) = index.add_path(&path) {
+            println!("⚠️ Cannot add file {:?}: {}", path, e);
         }
     }
     index.write()?;
@@ -369,6 +451,102 @@ pub fn get_commits_after_tag(tag: &str) -> Result<Vec<GitCommit>> {
     Ok(commits)
 }
 
+/// Get all commits reachable from `to` but not from `from`
+///
+/// When `from` is `None` the whole history of `to` is returned.
+pub fn get_commits_between(from: Option<&str>, to: &str) -> Result<Vec<GitCommit>> {
+    let repo = Repository::open(".")?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(repo.revparse_single(to)?.peel_to_commit()?.id())?;
+    if let Some(from) = from {
+        revwalk.hide(repo.revparse_single(from)?.peel_to_commit()?.id())?;
+    }
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        commits.push(GitCommit {
+            hash: commit.id().to_string(),
+            message: commit.message().unwrap_or("").trim().to_string(),
+        });
+    }
+
+    Ok(commits)
+}
+
+/// Find the local tag name matching `version`, ignoring prefix differences
+pub fn find_local_tag(repo: &Repository, version: &Version) -> Result<Option<String>> {
+    Ok(version_tags(repo)?
+        .into_iter()
+        .find(|v| v.version == version.version)
+        .map(|v| v.to_string()))
+}
+
+/// Get the highest local version tag lower than `version`
+///
+/// Tags are compared by semver precedence, so this works for releasing
+/// older versions too, and never depends on what is published remotely.
+pub fn get_previous_tag(repo: &Repository, version: &Version) -> Result<Option<Version>> {
+    Ok(version_tags(repo)?
+        .into_iter()
+        .filter(|v| v.version < version.version)
+        .max_by(|a, b| a.version.cmp(&b.version)))
+}
+
+/// 获取包的最新版本标签，例如 `api@1.4.0`
+pub fn get_latest_package_tag(
+    repo: &Repository,
+    package: &PackageConfig,
+) -> Result<Option<Version>> {
+    let template = TagTemplate::for_package(&LocalConfig::load()?, package)?;
+    Ok(get_local_tags(repo)?
+        .iter()
+        .filter_map(|tag| template.match_tag(tag))
+        .max_by(|a, b| a.version.cmp(&b.version)))
+}
+
+/// 获取 `from` 之后修改过 `path` 目录的提交
+///
+/// 每个提交与其父提交比较目录树，`from` 为 `None` 时遍历全部历史。
+/// 合并提交与第一个父提交的差异包含整个分支的改动，分支上的提交已单独列出，因此跳过。
+pub fn get_commits_touching(from: Option<&str>, path: &Path) -> Result<Vec<GitCommit>> {
+    let repo = Repository::open(".")?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    if let Some(from) = from {
+        revwalk.hide(repo.revparse_single(from)?.peel_to_commit()?.id())?;
+    }
+
+    let mut diff_options = git2::DiffOptions::new();
+    diff_options.pathspec(path);
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(
+            parent_tree.as_ref(),
+            Some(&commit.tree()?),
+            Some(&mut diff_options),
+        )?;
+        if diff.deltas().len() == 0 {
+            continue;
+        }
+        commits.push(GitCommit {
+            hash: commit.id().to_string(),
+            message: commit.message().unwrap_or("").trim().to_string(),
+        });
+    }
+
+    Ok(commits)
+}
+
 /// Get all local tags
 pub fn get_local_tags(repo: &Repository) -> Result<Vec<String>> {
     let tags = repo.tag_names(None)?;
@@ -1,163 +1,539 @@
 use super::version::Version;
 use anyhow::Result;
-use chrono::Local;
+use chrono::{Local, TimeZone};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
 use serde_json::json;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// 单次请求的总超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// 建立连接的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 5xx、网络错误与二级限流时的最大重试次数
+const MAX_RETRIES: u32 = 3;
+/// 等待 `Retry-After` 的最长时间，超过则直接报错
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+/// 生成 release notes 时最多查询的 PR 数量，超过后只使用提交标题中的编号
+const MAX_PULL_REQUEST_LOOKUPS: usize = 100;
+
+/// 所有 GitHub 请求共享的 HTTP 客户端
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .user_agent("RustyTag")
+            .timeout(REQUEST_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .expect("Failed to build HTTP client")
+    })
+}
+
+fn header_str<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+    response.headers().get(name).and_then(|v| v.to_str().ok())
+}
+
+/// 主限流耗尽时，生成包含重置时间的错误
+fn rate_limit_error(response: &Response) -> Option<anyhow::Error> {
+    if header_str(response, "x-ratelimit-remaining") != Some("0") {
+        return None;
+    }
+    let reset = header_str(response, "x-ratelimit-reset")
+        .and_then(|v| v.parse::<i64>().ok())
+        .and_then(|ts| Local.timestamp_opt(ts, 0).single())
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    Some(anyhow::anyhow!(
+        "GitHub API rate limit exceeded, resets at {}",
+        reset
+    ))
+}
+
+/// Release notes 的来源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum NotesSource {
+    /// 根据上一个标签以来的提交记录生成
+    #[default]
+    Commits,
+    /// 原样使用 CHANGELOG.md 中该版本的章节
+    Changelog,
+    /// 原样使用附注标签中的信息
+    Tag,
+    /// 使用 GitHub 的 generate-notes 接口生成
+    Github,
+}
+
+/// 提交对应的 Pull Request
+struct PullRequestInfo {
+    number: u64,
+    author: Option<String>,
+}
+
+/// 拆分提交标题末尾 squash/merge 产生的 `(#123)`
+///
+/// 返回去掉编号后的标题与编号，编号会单独生成链接；没有编号时原样返回标题。
+fn split_pr_suffix(subject: &str) -> (&str, Option<u64>) {
+    let subject = subject.trim_end();
+    let parsed = subject.rsplit_once("(#").and_then(|(head, rest)| {
+        let number = rest.strip_suffix(')')?.parse::<u64>().ok()?;
+        Some((head.trim_end(), number))
+    });
+    match parsed {
+        Some((head, number)) => (head, Some(number)),
+        None => (subject, None),
+    }
+}
+
+/// 从提交信息中解析 `Closes #45`、`Fixes #45`、`Resolves #45` 等关闭的 issue
+fn parse_closed_issues(message: &str) -> Vec<u64> {
+    const KEYWORDS: [&str; 9] = [
+        "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+    ];
+
+    let mut issues = Vec::new();
+    let words: Vec<&str> = message.split_whitespace().collect();
+    for pair in words.windows(2) {
+        let keyword = pair[0].trim_end_matches(':').to_lowercase();
+        if !KEYWORDS.contains(&keyword.as_str()) {
+            continue;
+        }
+        let number = pair[1]
+            .trim_end_matches(|c: char| !c.is_ascii_digit())
+            .strip_prefix('#')
+            .and_then(|n| n.parse::<u64>().ok());
+        if let Some(number) = number {
+            if !issues.contains(&number) {
+                issues.push(number);
+            }
+        }
+    }
+    issues
+}
 
 pub struct GitHubClient {
     token: String,
     repo_url: String,
+    /// API 根地址，GitHub Enterprise Server 为 `https://<host>/api/v3`
+    api_url: String,
+    http: &'static reqwest::Client,
 }
 
 impl GitHubClient {
-    pub fn new(token: String, repo_url: String) -> Self {
-        Self { token, repo_url }
+    pub fn new(token: String, repo_url: String, api_url: String) -> Self {
+        Self {
+            token,
+            repo_url,
+            api_url,
+            http: http_client(),
+        }
     }
 
-    /// 从环境变量或配置文件获取 GitHub token
-    pub fn from_env_or_config() -> Result<String> {
-        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-            Ok(token)
-        } else {
-            let config = crate::utils::config::GlobalConfig::load()?;
-            config.github_token.ok_or_else(|| {
-                anyhow::anyhow!(
-                    "GitHub token not found. To set it up:\n\
-                     1. Visit https://github.com/settings/tokens\n\
-                     2. Click 'Generate new token' (classic)\n\
-                     3. Select the 'repo' scope\n\
-                     4. Generate and copy the token\n\
-                     5. Set it using:\n\
-                        rustytag config --set GITHUB_TOKEN=your_token"
-                )
-            })
+    /// 为当前仓库的远程主机创建客户端，凭据按主机名选择
+    ///
+    /// 只支持 github.com 与 GitHub Enterprise Server，GitLab 等其他平台的主机直接报错。
+    pub fn for_remote() -> Result<Self> {
+        let repo_url = crate::utils::git::get_remote_url()?;
+        let host = crate::utils::git::remote_host(&repo_url)
+            .ok_or_else(|| anyhow::anyhow!("Failed to get host from remote URL {}", repo_url))?;
+        let provider = crate::utils::config::HostConfig::provider(&host)?;
+        if provider != crate::utils::config::Provider::Github {
+            return Err(anyhow::anyhow!(
+                "{} is a {} host, releases are only supported on GitHub and GitHub Enterprise Server",
+                host,
+                provider
+            ));
+        }
+        let token = Self::from_env_or_config(&host)?;
+        let api_url = crate::utils::config::HostConfig::api_url(&host)?;
+        Ok(Self::new(token, repo_url, api_url))
+    }
+
+    /// 仓库的 API 地址，例如 `https://api.github.com/repos/owner/repo`
+    fn repo_api_url(&self) -> String {
+        let parts: Vec<&str> = self.repo_url.trim_end_matches(".git").split('/').collect();
+        let owner = parts[parts.len() - 2];
+        let repo = parts[parts.len() - 1];
+        format!("{}/repos/{}/{}", self.api_url, owner, repo)
+    }
+
+    /// 构建带认证信息的请求
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        self.http
+            .request(method, url)
+            .header("Accept", "application/vnd.github.v3+json")
+            .header("Authorization", format!("token {}", self.token))
+    }
+
+    /// 发送请求，对 5xx、网络错误和二级限流进行指数退避重试
+    ///
+    /// 创建 release 等非幂等请求可能已经在服务端生效，只在连接失败或二级限流时重试，
+    /// 避免重复创建。主限流耗尽时直接返回包含重置时间的错误；其他响应原样返回给调用方。
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let idempotent = request
+            .try_clone()
+            .and_then(|r| r.build().ok())
+            .is_some_and(|r| r.method().is_idempotent());
+        let mut attempt = 0;
+        loop {
+            let backoff = Duration::from_secs(1 << attempt);
+            let current = request
+                .try_clone()
+                .ok_or_else(|| anyhow::anyhow!("Request body cannot be retried"))?;
+
+            let response = match current.send().await {
+                Ok(response) => response,
+                Err(e)
+                    if attempt < MAX_RETRIES
+                        && (e.is_connect() || (idempotent && e.is_timeout())) =>
+                {
+                    println!("⚠️ GitHub request failed ({}), retrying...", e);
+                    attempt += 1;
+                    tokio::time::sleep(backoff).await;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let status = response.status();
+            if status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS {
+                if let Some(err) = rate_limit_error(&response) {
+                    return Err(err);
+                }
+                // 二级限流：按 Retry-After 等待后重试
+                if let Some(wait) = header_str(&response, "retry-after")
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .map(Duration::from_secs)
+                {
+                    if attempt >= MAX_RETRIES || wait > MAX_RETRY_AFTER {
+                        return Err(anyhow::anyhow!(
+                            "GitHub API secondary rate limit hit, retry after {} seconds",
+                            wait.as_secs()
+                        ));
+                    }
+                    println!(
+                        "⚠️ GitHub secondary rate limit hit, waiting {} seconds...",
+                        wait.as_secs()
+                    );
+                    attempt += 1;
+                    tokio::time::sleep(wait.max(backoff)).await;
+                    continue;
+                }
+            }
+
+            if status.is_server_error() && idempotent && attempt < MAX_RETRIES {
+                println!("⚠️ GitHub returned {}, retrying...", status);
+                attempt += 1;
+                tokio::time::sleep(backoff).await;
+                continue;
+            }
+
+            return Ok(response);
+        }
+    }
+
+    /// 从分层配置获取访问 `host` 的 token（环境变量、钥匙串、token 文件或命令）
+    pub fn from_env_or_config(host: &str) -> Result<String> {
+        let token = crate::utils::secret::resolve_token(host)?;
+        if token.is_none() && host != crate::utils::secret::DEFAULT_HOST {
+            return Err(anyhow::anyhow!(
+                "No token configured for {}. Set it using:\n\
+                    rustytag config --host {} set token your_token",
+                host,
+                host
+            ));
         }
+        token.ok_or_else(|| {
+            anyhow::anyhow!(
+                "GitHub token not found. To set it up:\n\
+                 1. Visit https://github.com/settings/tokens\n\
+                 2. Click 'Generate new token' (classic)\n\
+                 3. Select the 'repo' scope\n\
+                 4. Generate and copy the token\n\
+                 5. Set it using:\n\
+                    rustytag config set github_token your_token\n\
+                 Or reuse the GitHub CLI login:\n\
+                    rustytag config set github_token_command \"gh auth token\""
+            )
+        })
     }
 
-    /// 获取最新的发布版本，通过 GitHub API 获取
-    pub async fn get_latest_release() -> Result<String> {
-        let repo_url = super::git::get_remote_url()?;
+    /// 创建 GitHub Release
+    pub async fn create_release(&self, version: &Version, notes: NotesSource) -> Result<()> {
+        // 从本地标签确定发布范围：目标标签与其之前的最近版本标签
+        let repo = git2::Repository::open(".")?;
+        let target = super::git::find_local_tag(&repo, version)?;
+        let tag_name = target.clone().unwrap_or_else(|| version.to_string());
+
+        let release_notes = match notes {
+            NotesSource::Commits => {
+                self.build_commit_notes(&repo, version, target.as_deref())
+                    .await?
+            }
+            NotesSource::Changelog => {
+                let path = super::config::LocalConfig::load()?
+                    .changelog_path()
+                    .ok_or_else(|| anyhow::anyhow!("CHANGELOG generation is disabled"))?;
+                let changelog = std::fs::read_to_string(&path)
+                    .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+                super::file::extract_changelog_section(&changelog, version).ok_or_else(|| {
+                    anyhow::anyhow!("No section for {} found in {}", version, path.display())
+                })?
+            }
+            NotesSource::Tag => {
+                let target = target
+                    .as_deref()
+                    .ok_or_else(|| anyhow::anyhow!("Tag {} does not exist locally", tag_name))?;
+                super::git::get_tag_message(&repo, target)?
+            }
+            NotesSource::Github => {
+                let previous = super::git::get_previous_tag(&repo, version)?;
+                self.generate_notes(&tag_name, previous.map(|v| v.to_string()).as_deref())
+                    .await?
+            }
+        };
+
+        let url = format!("{}/releases", self.repo_api_url());
+        let response = self
+            .send(self.request(Method::POST, &url).json(&json!({
+                "tag_name": tag_name,
+                "name": format!("Release {}", version),
+                "body": release_notes,
+                "draft": false,
+                "prerelease": false
+            })))
+            .await?;
 
-        // 从 repo_url 提取 owner 和 repo
-        let parts: Vec<&str> = repo_url.trim_end_matches(".git").split('/').collect();
-        if parts.len() < 2 {
-            return Ok("initial".to_string());
+        if response.status().is_success() {
+            println!("✨ Successfully created GitHub release for {}", version);
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Failed to create release: {}",
+                response.text().await?
+            ))
         }
+    }
 
-        let owner = parts[parts.len() - 2];
-        let repo = parts[parts.len() - 1];
+    /// 调用 GitHub 的 generate-notes 接口生成 release notes
+    ///
+    /// 仓库中存在 `.github/release.yml` 时使用其中的分类配置。
+    async fn generate_notes(&self, tag_name: &str, previous_tag: Option<&str>) -> Result<String> {
+        let mut payload = json!({ "tag_name": tag_name });
+        if let Some(previous_tag) = previous_tag {
+            payload["previous_tag_name"] = json!(previous_tag);
+        }
+        if let Some(config) = [".github/release.yml", ".github/release.yaml"]
+            .into_iter()
+            .find(|path| std::path::Path::new(path).exists())
+        {
+            payload["configuration_file_path"] = json!(config);
+        }
 
-        // 构建 GitHub API URL，只获取最新的一个发布
-        let api_url = format!(
-            "https://api.github.com/repos/{}/{}/releases?per_page=1",
-            owner, repo
-        );
-
-        // 发送请求获取最新的发布
-        let client = reqwest::Client::new();
-        let response = client
-            .get(&api_url)
-            .header("User-Agent", "RustyTag")
-            .send()
+        let url = format!("{}/releases/generate-notes", self.repo_api_url());
+        let response = self
+            .send(self.request(Method::POST, &url).json(&payload))
             .await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to generate release notes: {}",
+                response.text().await?
+            ));
+        }
+
+        let notes: serde_json::Value = response.json().await?;
+        Ok(notes["body"].as_str().unwrap_or("").to_string())
+    }
 
+    /// 查找引入某个提交的 Pull Request
+    ///
+    /// 仓库不存在对应 PR 或接口不可用时返回 `None`，由调用方退回到解析提交信息。
+    async fn find_pull_request(&self, sha: &str) -> Result<Option<PullRequestInfo>> {
+        let url = format!("{}/commits/{}/pulls", self.repo_api_url(), sha);
+        let response = self.send(self.request(Method::GET, &url)).await?;
         if !response.status().is_success() {
-            return Ok("initial".to_string());
+            return Ok(None);
         }
 
-        let releases: Vec<serde_json::Value> = response.json().await?;
+        let pulls: Vec<serde_json::Value> = response.json().await?;
+        Ok(pulls.first().and_then(|pr| {
+            Some(PullRequestInfo {
+                number: pr["number"].as_u64()?,
+                author: pr["user"]["login"].as_str().map(str::to_string),
+            })
+        }))
+    }
 
-        // 如果有发布版本，返回最新的标签名
-        if let Some(release) = releases.first() {
-            if let Some(tag_name) = release.get("tag_name").and_then(|v| v.as_str()) {
-                return Ok(tag_name.to_string());
-            }
+    /// 判断作者在上一个标签之前是否没有任何提交
+    async fn is_new_contributor(&self, login: &str, previous_tag: Option<&str>) -> Result<bool> {
+        let Some(previous_tag) = previous_tag else {
+            return Ok(true);
+        };
+
+        let url = format!("{}/commits", self.repo_api_url());
+        let response = self
+            .send(self.request(Method::GET, &url).query(&[
+                ("author", login),
+                ("sha", previous_tag),
+                ("per_page", "1"),
+            ]))
+            .await?;
+        if !response.status().is_success() {
+            return Ok(false);
         }
 
-        Ok("initial".to_string())
+        let commits: Vec<serde_json::Value> = response.json().await?;
+        Ok(commits.is_empty())
     }
 
-    /// 创建 GitHub Release
-    pub async fn create_release(&self, version: &Version) -> Result<()> {
-        // 获取上一个版本
-        let previous_version = Self::get_latest_release().await?;
+    /// 根据两个标签之间的提交记录生成 release notes
+    async fn build_commit_notes(
+        &self,
+        repo: &git2::Repository,
+        version: &Version,
+        target: Option<&str>,
+    ) -> Result<String> {
+        let previous_version = super::git::get_previous_tag(repo, version)?;
+        let tag_name = target.map_or_else(|| version.to_string(), str::to_string);
+        let base_url = self.repo_url.trim_end_matches(".git");
+        let date = Local::now().format("%Y-%m-%d");
 
         // 生成版本对比链接和标题
-        let (title, compare_url) = if previous_version == "initial" {
-            (
+        let (title, compare_url) = match &previous_version {
+            None => (
                 format!(
                     "### [{}]({}/commits/{}) ({})",
-                    version,
-                    self.repo_url.trim_end_matches(".git"),
-                    version,
-                    Local::now().format("%Y-%m-%d")
+                    version, base_url, tag_name, date
                 ),
                 None,
-            )
-        } else {
-            (
+            ),
+            Some(previous) => (
                 format!(
                     "### [{}]({}/compare/{}...{}) ({})",
-                    version,
-                    self.repo_url.trim_end_matches(".git"),
-                    previous_version,
-                    version,
-                    Local::now().format("%Y-%m-%d")
+                    version, base_url, previous, tag_name, date
                 ),
-                Some(format!(
-                    "{}/compare/{}...{}",
-                    self.repo_url.trim_end_matches(".git"),
-                    previous_version,
-                    version
-                )),
-            )
+                Some(format!("{}/compare/{}...{}", base_url, previous, tag_name)),
+            ),
         };
 
-        // 获取提交记录
-        let commits = if previous_version == "initial" {
-            crate::utils::git::get_git_commits()?
-        } else {
-            crate::utils::git::get_commits_after_tag(&previous_version)?
-        };
+        // 获取提交记录：目标标签尚未创建时以 HEAD 为终点
+        let previous_tag = previous_version.as_ref().map(|v| v.to_string());
+        let commits =
+            super::git::get_commits_between(previous_tag.as_deref(), target.unwrap_or("HEAD"))?;
 
         // 生成 release notes
         let mut release_notes = title;
         release_notes.push_str("\n\n### Commits\n\n");
 
+        // PR 查询失败（例如限流）或超过上限后不再查询，退回到解析提交标题
+        let mut pr_lookups = 0;
+        let mut pr_lookups_enabled = true;
+        let mut contributors: Vec<(String, String)> = Vec::new();
         for commit in commits {
             if commit.message.starts_with("chore: release") {
                 continue;
             }
 
-            // 获取提交者信息
-            let author = if let Some(author) = commit
-                .message
-                .lines()
-                .next()
-                .and_then(|line| line.rfind(" by @"))
-                .map(|pos| &commit.message[pos..])
-            {
-                author.to_string()
-            } else {
-                String::new()
+            let subject = commit.message.lines().next().unwrap_or("").trim();
+            // 移除提交信息中原有的作者信息，优先使用 PR 作者
+            let (subject, subject_author) = match subject.rsplit_once(" by @") {
+                Some((subject, author)) => (subject.trim(), Some(author.trim().to_string())),
+                None => (subject, None),
             };
 
-            release_notes.push_str(&format!(
-                "* {}{} ([{}]({}/commit/{}))\n",
-                commit
-                    .message
-                    .lines()
-                    .next()
-                    .unwrap_or("")
-                    .trim()
-                    .split(" by @")
-                    .next()
-                    .unwrap_or("")
-                    .trim(), // 移除原有的作者信息
-                author, // 添加作者信息
+            let (subject, subject_pr) = split_pr_suffix(subject);
+
+            let mut pull_request = None;
+            if pr_lookups_enabled && pr_lookups == MAX_PULL_REQUEST_LOOKUPS {
+                println!(
+                    "ℹ️  Looked up {} pull requests, using commit subjects for the rest",
+                    MAX_PULL_REQUEST_LOOKUPS
+                );
+                pr_lookups_enabled = false;
+            }
+            if pr_lookups_enabled {
+                pr_lookups += 1;
+                match self.find_pull_request(&commit.hash).await {
+                    Ok(pr) => pull_request = pr,
+                    Err(e) => {
+                        println!(
+                            "⚠️ Failed to look up pull requests ({}), using commit subjects instead",
+                            e
+                        );
+                        pr_lookups_enabled = false;
+                    }
+                }
+            }
+            let pr_number = pull_request.as_ref().map(|pr| pr.number).or(subject_pr);
+            let author = pull_request.and_then(|pr| pr.author).or(subject_author);
+
+            let mut line = format!("* {}", subject);
+            if let Some(author) = &author {
+                line.push_str(&format!(" by @{}", author));
+            }
+            if let Some(number) = pr_number {
+                line.push_str(&format!(" in [#{}]({}/pull/{})", number, base_url, number));
+            }
+            line.push_str(&format!(
+                " ([{}]({}/commit/{}))",
                 &commit.hash[..7],
-                self.repo_url.trim_end_matches(".git"),
+                base_url,
                 commit.hash
             ));
+            let issues = parse_closed_issues(&commit.message);
+            if !issues.is_empty() {
+                let links: Vec<String> = issues
+                    .iter()
+                    .map(|n| format!("[#{}]({}/issues/{})", n, base_url, n))
+                    .collect();
+                line.push_str(&format!(", closes {}", links.join(", ")));
+            }
+            release_notes.push_str(&line);
+            release_notes.push('\n');
+
+            // 记录每位作者首次出现的位置，用于 New Contributors
+            if let Some(author) = author {
+                if !contributors.iter().any(|(login, _)| *login == author) {
+                    let link = match pr_number {
+                        Some(number) => format!("[#{}]({}/pull/{})", number, base_url, number),
+                        None => format!(
+                            "[{}]({}/commit/{})",
+                            &commit.hash[..7],
+                            base_url,
+                            commit.hash
+                        ),
+                    };
+                    contributors.push((author, link));
+                }
+            }
+        }
+
+        // 每位作者只查询一次；查询失败时省略 New Contributors，避免列出不完整的名单
+        let mut new_contributors = Vec::new();
+        for (login, link) in contributors {
+            match self
+                .is_new_contributor(&login, previous_tag.as_deref())
+                .await
+            {
+                Ok(true) => new_contributors.push(format!(
+                    "* @{} made their first contribution in {}",
+                    login, link
+                )),
+                Ok(false) => {}
+                Err(e) => {
+                    println!(
+                        "⚠️ Failed to look up contributors ({}), skipping New Contributors",
+                        e
+                    );
+                    new_contributors.clear();
+                    break;
+                }
+            }
+        }
+        if !new_contributors.is_empty() {
+            release_notes.push_str("\n### New Contributors\n\n");
+            release_notes.push_str(&new_contributors.join("\n"));
+            release_notes.push('\n');
         }
 
         // 如果有对比链接，添加到 release notes
@@ -166,56 +542,13 @@ impl GitHubClient {
             release_notes.push_str(&format!("Full Changelog: {}", url));
         }
 
-        // 从 repo_url 提取 owner 和 repo
-        let parts: Vec<&str> = self.repo_url.trim_end_matches(".git").split('/').collect();
-        let owner = parts[parts.len() - 2];
-        let repo = parts[parts.len() - 1];
-
-        let client = reqwest::Client::new();
-        let url = format!("https://api.github.com/repos/{}/{}/releases", owner, repo);
-
-        let response = client
-            .post(url)
-            .header("Accept", "application/vnd.github.v3+json")
-            .header("Authorization", format!("token {}", self.token))
-            .header("User-Agent", "RustyTag")
-            .json(&json!({
-                "tag_name": version.to_string(),
-                "name": format!("Release {}", version),
-                "body": release_notes,
-                "draft": false,
-                "prerelease": false
-            }))
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            println!("✨ Successfully created GitHub release for {}", version);
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!(
-                "Failed to create release: {}",
-                response.text().await?
-            ))
-        }
+        Ok(release_notes)
     }
 
     /// 获取所有发布版本
     pub async fn list_releases(&self) -> Result<()> {
-        let parts: Vec<&str> = self.repo_url.trim_end_matches(".git").split('/').collect();
-        let owner = parts[parts.len() - 2];
-        let repo = parts[parts.len() - 1];
-
-        let client = reqwest::Client::new();
-        let url = format!("https://api.github.com/repos/{}/{}/releases", owner, repo);
-
-        let response = client
-            .get(&url)
-            .header("Accept", "application/vnd.github.v3+json")
-            .header("Authorization", format!("token {}", self.token))
-            .header("User-Agent", "RustyTag")
-            .send()
-            .await?;
+        let url = format!("{}/releases", self.repo_api_url());
+        let response = self.send(self.request(Method::GET, &url)).await?;
 
         if !response.status().is_success() {
             return Err(anyhow::anyhow!(
@@ -253,19 +586,13 @@ impl GitHubClient {
 }
 
 /// 创建 GitHub Release 的便捷函数
-pub async fn create_github_release(version: &Version) -> Result<()> {
-    let token = GitHubClient::from_env_or_config()?;
-    let repo_url = crate::utils::git::get_remote_url()?;
-
-    let client = GitHubClient::new(token, repo_url);
-    client.create_release(version).await
+pub async fn create_github_release(version: &Version, notes: NotesSource) -> Result<()> {
+    GitHubClient::for_remote()?
+        .create_release(version, notes)
+        .await
 }
 
 /// 列出所有 GitHub Release 的便捷函数
 pub async fn list_github_releases() -> Result<()> {
-    let token = GitHubClient::from_env_or_config()?;
-    let repo_url = crate::utils::git::get_remote_url()?;
-
-    let client = GitHubClient::new(token, repo_url);
-    client.list_releases().await
+    GitHubClient::for_remote()?.list_releases().await
 }
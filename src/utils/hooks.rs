@@ -0,0 +1,51 @@
+use anyhow::Result;
+use std::process::Command;
+
+use super::version::Version;
+
+// 升级前后的钩子命令
+//
+// 命令通过系统 shell 执行，版本号以环境变量传入：
+// - `RUSTYTAG_VERSION`：新版本（包含前缀）
+// - `RUSTYTAG_PREVIOUS_VERSION`：升级前的版本
+
+/// 依次执行钩子命令，任何命令失败时返回错误
+pub fn run_hooks(
+    stage: &str,
+    commands: &[String],
+    version: &Version,
+    previous: &Version,
+) -> Result<()> {
+    for command in commands {
+        println!("🪝 [{}] {}", stage, command);
+        let status = shell(command)
+            .env("RUSTYTAG_VERSION", version.to_string())
+            .env("RUSTYTAG_PREVIOUS_VERSION", previous.to_string())
+            .status()
+            .map_err(|e| anyhow::anyhow!("Failed to run {} hook `{}`: {}", stage, command, e))?;
+        if !status.success() {
+            return Err(anyhow::anyhow!(
+                "{} hook `{}` failed with {}",
+                stage,
+                command,
+                status
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 通过系统 shell 执行命令
+pub fn shell(command: &str) -> Command {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    shell
+}
@@ -0,0 +1,194 @@
+use anyhow::Result;
+use std::ops::Range;
+
+// 保留格式的 JSON 编辑
+//
+// 只替换目标字符串值所在的字节范围，缩进、键顺序和结尾换行都保持原样。
+
+/// 替换 `path` 指向的字符串值，返回新内容
+///
+/// 路径不存在或目标不是字符串时返回 `None`。
+pub fn replace_string_value(
+    content: &str,
+    path: &[&str],
+    new_value: &str,
+) -> Result<Option<String>> {
+    let Some(range) = find_string_value(content, path)? else {
+        return Ok(None);
+    };
+    replace_range(content, range, new_value).map(Some)
+}
+
+/// 将 `range` 处的值替换为字符串
+fn replace_range(content: &str, range: Range<usize>, new_value: &str) -> Result<String> {
+    let mut updated = String::with_capacity(content.len());
+    updated.push_str(&content[..range.start]);
+    updated.push_str(&serde_json::to_string(new_value)?);
+    updated.push_str(&content[range.end..]);
+    Ok(updated)
+}
+
+/// 读取 `path` 指向的字符串值
+pub fn read_string_value(content: &str, path: &[&str]) -> Result<Option<String>> {
+    Ok(match find_string_value(content, path)? {
+        Some(range) => Some(serde_json::from_str(&content[range])?),
+        None => None,
+    })
+}
+
+/// 设置顶层字符串字段，字段不存在时插入为对象的第一个成员
+///
+/// 已有的字段无论原来是什么类型（例如 `null`）都直接替换，不会产生重复的键；
+/// 插入时沿用第一个成员前的缩进，保持文件原有风格。
+pub fn set_top_level_string(content: &str, key: &str, new_value: &str) -> Result<String> {
+    if let Some(range) = find_value(content, &[key])? {
+        return replace_range(content, range, new_value);
+    }
+
+    let open = content
+        .find('{')
+        .ok_or_else(|| anyhow::anyhow!("Expected a JSON object"))?;
+    let body = &content[open + 1..];
+    let indent_len = body.len() - body.trim_start().len();
+    let indent = &body[..indent_len];
+    let member = format!(
+        "{}: {}",
+        serde_json::to_string(key)?,
+        serde_json::to_string(new_value)?
+    );
+
+    let mut updated = String::with_capacity(content.len() + member.len() + 8);
+    updated.push_str(&content[..=open]);
+    if body.trim_start().starts_with('}') {
+        updated.push_str(&format!("\n  {}\n", member));
+        updated.push_str(body.trim_start());
+    } else {
+        updated.push_str(indent);
+        updated.push_str(&member);
+        updated.push(',');
+        updated.push_str(body);
+    }
+    Ok(updated)
+}
+
+/// 查找 `path` 指向的字符串值的字节范围（包含引号）
+fn find_string_value(content: &str, path: &[&str]) -> Result<Option<Range<usize>>> {
+    Ok(find_value(content, path)?.filter(|r| content.as_bytes()[r.start] == b'"'))
+}
+
+/// 查找 `path` 指向的任意类型值的字节范围
+fn find_value(content: &str, path: &[&str]) -> Result<Option<Range<usize>>> {
+    // 先完整解析一遍，之后的扫描可以假设输入是合法 JSON
+    serde_json::from_str::<serde_json::Value>(content)?;
+    let mut scanner = Scanner {
+        bytes: content.as_bytes(),
+        pos: 0,
+    };
+    scanner.skip_whitespace();
+    Ok(scanner.find(path))
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> u8 {
+        self.bytes.get(self.pos).copied().unwrap_or(0)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    /// 在当前位置的值中查找路径，找到时返回值的字节范围
+    fn find(&mut self, path: &[&str]) -> Option<Range<usize>> {
+        let Some((key, rest)) = path.split_first() else {
+            let start = self.pos;
+            self.skip_value();
+            return Some(start..self.pos);
+        };
+        if self.peek() != b'{' {
+            return None;
+        }
+
+        self.pos += 1;
+        loop {
+            self.skip_whitespace();
+            if self.peek() == b'}' {
+                return None;
+            }
+            let name = self.string();
+            self.skip_whitespace();
+            self.pos += 1; // ':'
+            self.skip_whitespace();
+            if serde_json::from_slice::<String>(&self.bytes[name])
+                .ok()
+                .as_deref()
+                == Some(*key)
+            {
+                return self.find(rest);
+            }
+            self.skip_value();
+            self.skip_whitespace();
+            if self.peek() == b',' {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn string(&mut self) -> Range<usize> {
+        let start = self.pos;
+        self.pos += 1;
+        while self.pos < self.bytes.len() {
+            match self.bytes[self.pos] {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => self.pos += 1,
+            }
+        }
+        start..self.pos
+    }
+
+    fn skip_value(&mut self) {
+        match self.peek() {
+            b'"' => {
+                self.string();
+            }
+            b'{' | b'[' => {
+                let mut depth = 0;
+                while self.pos < self.bytes.len() {
+                    match self.peek() {
+                        b'"' => {
+                            self.string();
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return;
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                while !matches!(self.peek(), b',' | b'}' | b']' | 0)
+                    && !self.peek().is_ascii_whitespace()
+                {
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+}
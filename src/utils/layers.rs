@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+use super::config::{ConfigKey, GlobalConfig, LocalConfig, CONFIG_KEYS};
+
+// 分层配置
+//
+// 每个配置项按以下顺序解析，后面的层覆盖前面的层：
+// 默认值 → 全局配置 → 项目配置 → `RUSTYTAG_*` 环境变量 → 命令行 `-c KEY=VALUE`
+//
+// CI 中无法写入配置文件时，可以只用环境变量配置。
+
+/// 配置层
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Default,
+    Global,
+    Project,
+    Env,
+    Cli,
+}
+
+impl std::fmt::Display for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Default => "default",
+            Self::Global => "global",
+            Self::Project => "project",
+            Self::Env => "env",
+            Self::Cli => "cli",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 一层配置的内容
+pub struct LayerValues {
+    pub layer: Layer,
+    /// 该层的来源，例如文件路径
+    pub origin: String,
+    /// 敏感信息的实际来源，例如系统钥匙串
+    pub secret_origin: Option<String>,
+    pub config: Value,
+}
+
+impl LayerValues {
+    fn new(layer: Layer, origin: impl Into<String>) -> Self {
+        Self {
+            layer,
+            origin: origin.into(),
+            secret_origin: None,
+            config: Value::Object(Map::new()),
+        }
+    }
+}
+
+/// 兼容 GitHub Actions 等环境中常见的变量名，优先级低于 `RUSTYTAG_*`
+const LEGACY_ENV_VARS: [(&str, &str); 2] = [
+    ("github_token", "GITHUB_TOKEN"),
+    ("github_token_file", "GITHUB_TOKEN_FILE"),
+];
+
+/// 配置项在环境变量层中实际使用的变量名
+fn env_origin(key: &ConfigKey) -> String {
+    if std::env::var(key.env_var()).is_err() {
+        if let Some((_, var)) = LEGACY_ENV_VARS
+            .iter()
+            .find(|(name, var)| *name == key.name && std::env::var(var).is_ok())
+        {
+            return var.to_string();
+        }
+    }
+    key.env_var()
+}
+
+/// 配置项在某一层中的来源
+pub fn key_origin(key: &ConfigKey, layer: &LayerValues) -> String {
+    match layer.layer {
+        Layer::Env => env_origin(key),
+        Layer::Cli => format!("-c {}=...", key.name),
+        _ if key.secret => layer
+            .secret_origin
+            .clone()
+            .unwrap_or_else(|| layer.origin.clone()),
+        _ => layer.origin.clone(),
+    }
+}
+
+static CLI_OVERRIDES: OnceLock<Vec<(String, String)>> = OnceLock::new();
+
+/// 记录命令行中的 `-c KEY=VALUE`，启动时调用一次
+pub fn set_cli_overrides(overrides: &[String]) -> Result<()> {
+    let mut parsed = Vec::new();
+    for item in overrides {
+        let (key, value) = item
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid override {}. Use KEY=VALUE", item))?;
+        ConfigKey::find(key)?.parse(value)?;
+        parsed.push((key.to_string(), value.to_string()));
+    }
+    CLI_OVERRIDES
+        .set(parsed)
+        .map_err(|_| anyhow::anyhow!("Command line overrides are already set"))
+}
+
+/// 按优先级从低到高读取所有配置层
+///
+/// `secrets` 为真时从钥匙串读取 token，只有用到 token 的地方才需要。
+pub fn load_layers(secrets: bool) -> Result<Vec<LayerValues>> {
+    let mut defaults = LayerValues::new(Layer::Default, "built-in");
+    for key in CONFIG_KEYS {
+        if let Some(default) = key.default {
+            key.write(&mut defaults.config, Some(key.parse(default)?))?;
+        }
+    }
+
+    let mut global = LayerValues::new(
+        Layer::Global,
+        GlobalConfig::loaded_path().display().to_string(),
+    );
+    let global_config = if secrets {
+        GlobalConfig::load_with_secrets()?
+    } else {
+        GlobalConfig::load()?
+    };
+    global.secret_origin = global_config.token_store.as_ref().map(|s| s.to_string());
+    global.config = serde_json::to_value(global_config)?;
+
+    let project_config = LocalConfig::load_file()?;
+    let mut project = LayerValues::new(Layer::Project, project_config.source.to_string());
+    project.config = serde_json::to_value(&project_config)?;
+
+    let mut env = LayerValues::new(Layer::Env, "environment");
+    for key in CONFIG_KEYS {
+        if let Ok(raw) = std::env::var(key.env_var()) {
+            let value = key
+                .parse(&raw)
+                .with_context(|| format!("Invalid value in {}", key.env_var()))?;
+            key.write(&mut env.config, Some(value))?;
+        }
+    }
+    for (name, var) in LEGACY_ENV_VARS {
+        let key = ConfigKey::find(name)?;
+        if key.lookup(&env.config).is_none() {
+            if let Ok(raw) = std::env::var(var) {
+                key.write(&mut env.config, Some(raw.into()))?;
+            }
+        }
+    }
+
+    let mut cli = LayerValues::new(Layer::Cli, "command line");
+    for (key, raw) in CLI_OVERRIDES.get().into_iter().flatten() {
+        let key = ConfigKey::find(key)?;
+        key.write(&mut cli.config, Some(key.parse(raw)?))?;
+    }
+
+    Ok(vec![defaults, global, project, env, cli])
+}
+
+/// 合并所有配置层
+pub fn resolve() -> Result<Value> {
+    let mut resolved = Value::Object(Map::new());
+    for layer in load_layers(false)? {
+        merge(&mut resolved, layer.config);
+    }
+    Ok(resolved)
+}
+
+/// 解析单个配置项，返回生效的值与所在的层
+pub fn resolve_key(key: &ConfigKey) -> Result<Option<(Value, LayerValues)>> {
+    Ok(load_layers(key.secret)?
+        .into_iter()
+        .rev()
+        .find_map(|layer| Some((key.lookup(&layer.config)?.clone(), layer))))
+}
+
+/// 深度合并，`overlay` 中的表逐键覆盖 `base`，其余值整体替换
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                if value.is_null() {
+                    continue;
+                }
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
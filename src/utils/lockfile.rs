@@ -0,0 +1,101 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use toml_edit::Document;
+
+use super::edit::FileEdits;
+use super::json::replace_string_value;
+use super::project::{ProjectFile, ProjectFileType};
+use super::version::{set_version_value, Version};
+
+// 锁文件同步
+//
+// 清单文件的版本号变更后，锁文件中记录的根包版本也需要同步更新，
+// 否则下一次安装依赖时会产生与发布无关的改动。
+
+/// 获取项目文件旁边存在的锁文件
+pub fn lock_files(file: &ProjectFile) -> Vec<PathBuf> {
+    let names: &[&str] = match file.file_type {
+        ProjectFileType::CargoToml => &["Cargo.lock"],
+        ProjectFileType::PackageJson => &["package-lock.json", "npm-shrinkwrap.json"],
+        ProjectFileType::PyProjectToml => &["uv.lock"],
+        _ => &[],
+    };
+    let dir = file.path.parent().unwrap_or(Path::new(""));
+    names
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// 更新项目文件对应锁文件中的根包版本
+///
+/// Cargo.lock 由 Cargo 工作区更新一并处理；修改暂存在 `edits` 中。
+pub fn update_lock_files(
+    file: &ProjectFile,
+    version: &Version,
+    edits: &mut FileEdits,
+) -> Result<()> {
+    for lock in lock_files(file) {
+        match file.file_type {
+            ProjectFileType::PackageJson => update_npm_lock(&lock, version, edits)?,
+            ProjectFileType::PyProjectToml => update_uv_lock(&file.path, &lock, version, edits)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 更新 package-lock.json / npm-shrinkwrap.json 的 `version` 与 `packages[""].version`
+fn update_npm_lock(path: &Path, version: &Version, edits: &mut FileEdits) -> Result<()> {
+    let mut content = edits.read(path)?;
+    let new_version = version.version.to_string();
+    for key_path in [&["version"][..], &["packages", "", "version"]] {
+        if let Some(updated) = replace_string_value(&content, key_path, &new_version)? {
+            content = updated;
+        }
+    }
+    edits.write(path, content);
+    Ok(())
+}
+
+/// 更新 uv.lock 中项目自身（editable 或 virtual 源）的版本号
+fn update_uv_lock(
+    manifest: &Path,
+    path: &Path,
+    version: &Version,
+    edits: &mut FileEdits,
+) -> Result<()> {
+    let pyproject = edits.read(manifest)?.parse::<Document>()?;
+    let Some(name) = pyproject
+        .get("project")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+    else {
+        return Ok(());
+    };
+
+    let mut doc = edits.read(path)?.parse::<Document>()?;
+    let Some(packages) = doc
+        .get_mut("package")
+        .and_then(|p| p.as_array_of_tables_mut())
+    else {
+        return Ok(());
+    };
+    for package in packages.iter_mut() {
+        let is_project = package.get("name").and_then(|n| n.as_str()) == Some(name)
+            && package
+                .get("source")
+                .and_then(|s| s.as_inline_table())
+                .is_some_and(|s| s.contains_key("editable") || s.contains_key("virtual"));
+        if !is_project {
+            continue;
+        }
+        if let Some(item) = package.get_mut("version") {
+            set_version_value(item, &version.version.to_string());
+        }
+    }
+
+    edits.write(path, doc.to_string());
+    Ok(())
+}
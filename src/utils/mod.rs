@@ -1,6 +1,17 @@
+pub mod cargo;
+pub mod check;
 pub mod config;
+pub mod edit;
 pub mod file;
 pub mod git;
 pub mod github;
+pub mod hooks;
+pub mod json;
+pub mod layers;
+pub mod lockfile;
 pub mod project;
+pub mod replace;
+pub mod secret;
+pub mod tag;
 pub mod version;
+pub mod xml;
@@ -1,13 +1,91 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use regex::Regex;
+use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use toml_edit::Document;
 
+use super::cargo::workspace_members;
+use super::config::{DiscoveryConfig, LocalConfig};
+use super::json::read_string_value;
+use super::xml::find_element_text;
+
+/// 递归查找时总是跳过的目录
+const SKIPPED_DIRS: [&str; 9] = [
+    ".git",
+    "node_modules",
+    "target",
+    "vendor",
+    "dist",
+    "build",
+    ".venv",
+    "venv",
+    "__pycache__",
+];
+
+/// `gradle.properties` 中的 `version=1.0.0`
+pub const GRADLE_PROPERTIES_VERSION: &str = r"(?m)^([ \t]*version[ \t]*[=:][ \t]*)(\S+)";
+
+/// `build.gradle(.kts)` 中的 `version = "1.0.0"` 或 `version '1.0.0'`
+pub const BUILD_GRADLE_VERSION: &str = r#"(?m)^([ \t]*version[ \t]*=?[ \t]*["'])([^"'\n]*)(["'])"#;
+
+/// Chart.yaml / pubspec.yaml 顶层的 `version: 1.0.0`，保留 pubspec 的 `+build` 构建号
+pub const YAML_VERSION: &str = r#"(?m)^(version:[ \t]*["']?)([^"'\s#+]+)"#;
+
+/// Chart.yaml 顶层的 `appVersion: 1.0.0`
+pub const HELM_APP_VERSION: &str = r#"(?m)^(appVersion:[ \t]*["']?)([^"'\s#]+)"#;
+
+/// mix.exs 中的 `@version "1.0.0"` 或 `version: "1.0.0"`
+pub const MIX_EXS_VERSION: &str = r#"(?m)^([ \t]*(?:@version[ \t]+|version:[ \t]*)")([^"\n]*)(")"#;
+
+/// Ruby gem `version.rb` 中的 `VERSION = "1.0.0"`
+pub const RUBY_VERSION: &str = r#"(?m)^([ \t]*VERSION[ \t]*=[ \t]*["'])([^"'\n]*)(["'])"#;
+
+/// 纯文本 VERSION 文件的内容
+pub const PLAIN_VERSION: &str = r"\A(\s*)(\S+)";
+
+/// .csproj / Directory.Build.props 中的 `<Version>`
+pub const MSBUILD_VERSION_PATH: [&str; 3] = ["Project", "PropertyGroup", "Version"];
+
+/// pyproject.toml 中可能保存版本号的表，按优先级排列
+pub const PYPROJECT_VERSION_TABLES: [&[&str]; 3] =
+    [&["project"], &["tool", "poetry"], &["tool", "pdm"]];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProjectFileType {
     CargoToml,
     PackageJson,
     PyProjectToml,
+    PomXml,
+    GradleProperties,
+    BuildGradle,
+    HelmChart,
+    MsBuild,
+    Pubspec,
+    ComposerJson,
+    MixExs,
+    RubyVersion,
+    VersionFile,
     Other,
 }
 
+impl ProjectFileType {
+    /// 以正则定位版本号的文件类型，第二个捕获组为版本号
+    pub fn version_pattern(&self) -> Option<&'static str> {
+        match self {
+            Self::GradleProperties => Some(GRADLE_PROPERTIES_VERSION),
+            Self::BuildGradle => Some(BUILD_GRADLE_VERSION),
+            Self::HelmChart | Self::Pubspec => Some(YAML_VERSION),
+            Self::MixExs => Some(MIX_EXS_VERSION),
+            Self::RubyVersion => Some(RUBY_VERSION),
+            Self::VersionFile => Some(PLAIN_VERSION),
+            _ => None,
+        }
+    }
+}
+
 pub struct ProjectFile {
     pub path: PathBuf,
     pub file_type: ProjectFileType,
@@ -15,41 +93,275 @@ pub struct ProjectFile {
 
 impl ProjectFile {
     pub fn detect_all() -> Result<Vec<Self>> {
-        let mut files = Vec::new();
+        let mut files = Self::detect_in(Path::new(""))?;
+        let mut detect = |path: PathBuf, file_type: ProjectFileType| {
+            println!("Detected {} file", path.display());
+            files.push(Self { path, file_type });
+        };
 
-        // 检查并添加所有存在的项目文件
-        if Path::new("Cargo.toml").exists() {
-            println!("Detected Cargo.toml file");
-            files.push(Self {
-                path: PathBuf::from("Cargo.toml"),
-                file_type: ProjectFileType::CargoToml,
-            });
+        // monorepo：递归查找子目录中的清单文件，独立版本的包由 `--package` 单独升级
+        let config = LocalConfig::load()?;
+        if let Some(discovery) = config.discovery.filter(|d| d.recursive) {
+            let members = if Path::new("Cargo.toml").exists() {
+                workspace_members(Path::new(""))?
+            } else {
+                Vec::new()
+            };
+            for (path, file_type) in discover_nested(&discovery)? {
+                // Cargo 工作区成员已随根 Cargo.toml 一并更新
+                if !members.contains(&path) && !config.packages.iter().any(|p| p.contains(&path)) {
+                    detect(path, file_type);
+                }
+            }
         }
 
-        if Path::new("package.json").exists() {
-            println!("Detected package.json file");
+        // 旧版本会把版本号写入 .rustytag.json，仅在其中已有版本号时继续同步
+        let legacy = PathBuf::from(".rustytag.json");
+        if declares_version(&legacy, ProjectFileType::Other) {
+            println!("Detected rustytag project file: .rustytag.json");
             files.push(Self {
-                path: PathBuf::from("package.json"),
-                file_type: ProjectFileType::PackageJson,
+                path: legacy,
+                file_type: ProjectFileType::Other,
             });
         }
 
-        if Path::new("pyproject.toml").exists() {
-            println!("Detected pyproject.toml file");
-            files.push(Self {
-                path: PathBuf::from("pyproject.toml"),
-                file_type: ProjectFileType::PyProjectToml,
-            });
+        Ok(files)
+    }
+
+    /// 检测指定目录下（不递归）的项目文件
+    pub fn detect_in(dir: &Path) -> Result<Vec<Self>> {
+        let mut files = Vec::new();
+        let mut detect = |path: PathBuf, file_type: ProjectFileType| {
+            println!("Detected {} file", path.display());
+            files.push(Self { path, file_type });
+        };
+
+        // 检查并添加所有存在的项目文件
+        for (name, file_type) in [
+            ("Cargo.toml", ProjectFileType::CargoToml),
+            ("package.json", ProjectFileType::PackageJson),
+            ("pyproject.toml", ProjectFileType::PyProjectToml),
+            ("pom.xml", ProjectFileType::PomXml),
+        ] {
+            if dir.join(name).exists() {
+                detect(dir.join(name), file_type);
+            }
         }
 
-        if Path::new(".rustytag.json").exists() {
-            println!("Detected rustytag project file: .rustytag.json");
-            files.push(Self {
-                path: PathBuf::from(".rustytag.json"),
-                file_type: ProjectFileType::Other,
-            });
+        // 以下文件不一定声明版本号（例如 Gradle 的版本可能只写在其中一处），只检测实际声明了版本的文件
+        let mut candidates: Vec<(PathBuf, ProjectFileType)> = [
+            ("gradle.properties", ProjectFileType::GradleProperties),
+            ("build.gradle", ProjectFileType::BuildGradle),
+            ("build.gradle.kts", ProjectFileType::BuildGradle),
+            ("Chart.yaml", ProjectFileType::HelmChart),
+            ("pubspec.yaml", ProjectFileType::Pubspec),
+            ("mix.exs", ProjectFileType::MixExs),
+            ("VERSION", ProjectFileType::VersionFile),
+            ("Directory.Build.props", ProjectFileType::MsBuild),
+            ("composer.json", ProjectFileType::ComposerJson),
+        ]
+        .into_iter()
+        .map(|(name, file_type)| (dir.join(name), file_type))
+        .collect();
+        for path in glob::glob(&dir.join("*.csproj").to_string_lossy())?.flatten() {
+            candidates.push((path, ProjectFileType::MsBuild));
+        }
+        for path in glob::glob(&dir.join("lib/**/version.rb").to_string_lossy())?.flatten() {
+            candidates.push((path, ProjectFileType::RubyVersion));
+        }
+        for (path, file_type) in candidates {
+            if declares_version(&path, file_type) {
+                detect(path, file_type);
+            }
         }
 
         Ok(files)
     }
+
+    /// 读取文件中声明的版本号原文；版本由构建后端动态提供时返回 `None`
+    pub fn read_version_text(&self) -> Result<Option<String>> {
+        let path = self.path.as_path();
+        let content = fs::read_to_string(path)?;
+        let missing = || anyhow::anyhow!("No version found in {}", path.display());
+        match self.file_type {
+            ProjectFileType::CargoToml => {
+                let doc = content.parse::<Document>()?;
+                toml_version(&doc, &[&["package"], &["workspace", "package"]])
+                    .map(Some)
+                    .ok_or_else(missing)
+            }
+            ProjectFileType::PyProjectToml => {
+                let doc = content.parse::<Document>()?;
+                match toml_version(&doc, &PYPROJECT_VERSION_TABLES) {
+                    Some(version) => Ok(Some(version)),
+                    None if has_dynamic_version(&doc) => Ok(None),
+                    None => Err(missing()),
+                }
+            }
+            ProjectFileType::PackageJson
+            | ProjectFileType::ComposerJson
+            | ProjectFileType::Other => read_string_value(&content, &["version"])?
+                .map(Some)
+                .ok_or_else(missing),
+            file_type => {
+                let range = version_range(path, &content, file_type)?;
+                Ok(Some(content[range].to_string()))
+            }
+        }
+    }
+
+    /// 读取文件中声明的版本号；版本由构建后端动态提供时返回 `None`
+    pub fn read_version(&self) -> Result<Option<semver::Version>> {
+        self.read_version_text()?
+            .map(|text| {
+                semver::Version::parse(&text)
+                    .with_context(|| format!("Invalid version {} in {}", text, self.path.display()))
+            })
+            .transpose()
+    }
+}
+
+/// 读取所有项目文件中声明的版本号
+///
+/// 跳过没有声明版本、版本由构建后端提供或版本号不是语义化版本（如 `1.0-SNAPSHOT`）的文件，
+/// 单个文件无法读取不影响其他文件；`rustytag check` 会逐个报告这些文件。
+pub fn read_project_versions() -> Result<Vec<(PathBuf, semver::Version)>> {
+    let mut versions = Vec::new();
+    for file in ProjectFile::detect_all()? {
+        if let Ok(Some(version)) = file.read_version() {
+            versions.push((file.path, version));
+        }
+    }
+    Ok(versions)
+}
+
+/// 按顺序查找第一个声明了字符串 `version` 的 TOML 表
+fn toml_version(doc: &Document, tables: &[&[&str]]) -> Option<String> {
+    tables.iter().find_map(|table| {
+        table
+            .iter()
+            .try_fold(doc.as_item(), |item, key| item.get(key))?
+            .get("version")?
+            .as_str()
+            .map(String::from)
+    })
+}
+
+/// PEP 621: 版本由构建后端动态提供（如 hatch-vcs、setuptools-scm）
+pub(crate) fn has_dynamic_version(doc: &Document) -> bool {
+    doc.get("project")
+        .and_then(|project| project.get("dynamic"))
+        .and_then(|dynamic| dynamic.as_array())
+        .is_some_and(|dynamic| dynamic.iter().any(|v| v.as_str() == Some("version")))
+}
+
+/// 定位 pom.xml、MSBuild 以及按正则匹配的文件中版本号的字节范围
+///
+/// pom.xml 只匹配 `<project><version>`，忽略 parent 与依赖中的版本；
+/// 版本写成 `${revision}` 这类属性引用时，返回 `<properties>` 中对应属性的范围。
+pub(crate) fn version_range(
+    path: &Path,
+    content: &str,
+    file_type: ProjectFileType,
+) -> Result<Range<usize>> {
+    match file_type {
+        ProjectFileType::PomXml => {
+            let range = find_element_text(content, &["project", "version"]).ok_or_else(|| {
+                anyhow::anyhow!("No <project><version> found in {}", path.display())
+            })?;
+            match content[range.clone()]
+                .strip_prefix("${")
+                .and_then(|p| p.strip_suffix('}'))
+            {
+                Some(property) => find_element_text(content, &["project", "properties", property])
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Property {} not found in {}", property, path.display())
+                    }),
+                None => Ok(range),
+            }
+        }
+        ProjectFileType::MsBuild => find_element_text(content, &MSBUILD_VERSION_PATH)
+            .ok_or_else(|| anyhow::anyhow!("No <Version> found in {}", path.display())),
+        file_type => {
+            let pattern = file_type
+                .version_pattern()
+                .ok_or_else(|| anyhow::anyhow!("Unsupported version file {}", path.display()))?;
+            pattern_range(path, content, pattern)
+        }
+    }
+}
+
+/// 正则第二个捕获组（版本号）的字节范围
+pub(crate) fn pattern_range(path: &Path, content: &str, pattern: &str) -> Result<Range<usize>> {
+    Regex::new(pattern)?
+        .captures(content)
+        .and_then(|caps| caps.get(2))
+        .map(|m| m.range())
+        .ok_or_else(|| anyhow::anyhow!("No version declaration found in {}", path.display()))
+}
+
+/// 文件存在且包含版本声明
+fn declares_version(path: &Path, file_type: ProjectFileType) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+    match file_type {
+        ProjectFileType::MsBuild => find_element_text(&content, &MSBUILD_VERSION_PATH).is_some(),
+        ProjectFileType::ComposerJson | ProjectFileType::Other => {
+            serde_json::from_str::<serde_json::Value>(&content)
+                .is_ok_and(|json| json["version"].is_string())
+        }
+        _ => file_type
+            .version_pattern()
+            .and_then(|pattern| Regex::new(pattern).ok())
+            .is_some_and(|re| re.is_match(&content)),
+    }
+}
+
+/// 递归查找子目录中的 Cargo.toml、package.json、pyproject.toml
+///
+/// 遵循 .gitignore，并跳过依赖与构建输出目录；根目录下的文件由调用方单独处理。
+fn discover_nested(discovery: &DiscoveryConfig) -> Result<Vec<(PathBuf, ProjectFileType)>> {
+    let build_set = |patterns: &[String]| -> Result<GlobSet> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern)?);
+        }
+        Ok(builder.build()?)
+    };
+    let include = build_set(&discovery.include)?;
+    let exclude = build_set(&discovery.exclude)?;
+
+    let walker = WalkBuilder::new(".")
+        .hidden(false)
+        .filter_entry(|entry| {
+            !entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| SKIPPED_DIRS.contains(&name))
+        })
+        .build();
+
+    let mut files = Vec::new();
+    for entry in walker {
+        let entry = entry?;
+        let path = entry.path().strip_prefix(".").unwrap_or(entry.path());
+        // 根目录的文件已经检测过
+        if path.parent().is_none_or(|p| p.as_os_str().is_empty()) {
+            continue;
+        }
+        let file_type = match path.file_name().and_then(|n| n.to_str()) {
+            Some("Cargo.toml") => ProjectFileType::CargoToml,
+            Some("package.json") => ProjectFileType::PackageJson,
+            Some("pyproject.toml") => ProjectFileType::PyProjectToml,
+            _ => continue,
+        };
+        if (!discovery.include.is_empty() && !include.is_match(path)) || exclude.is_match(path) {
+            continue;
+        }
+        files.push((path.to_path_buf(), file_type));
+    }
+
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
 }
@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::path::PathBuf;
+
+use super::config::FileReplacer;
+use super::edit::FileEdits;
+use super::version::Version;
+
+// 自定义版本替换规则
+//
+// 用于清单解析器覆盖不到的位置，例如 README 中的安装示例、
+// 源码里的 `const VERSION: &str`、Dockerfile 的 `LABEL version=`。
+//
+// `search` 是正则表达式，`{{version}}` 与 `{{previous}}` 会先替换为转义后的版本号；
+// `replace` 中同样可以使用这两个占位符以及 `$1` 等捕获组引用。
+
+/// 展开规则中的 glob，得到所有匹配的文件
+pub fn replacer_paths(replacer: &FileReplacer) -> Result<Vec<PathBuf>> {
+    let paths: Vec<PathBuf> = glob::glob(&replacer.path)
+        .with_context(|| format!("Invalid file pattern {}", replacer.path))?
+        .flatten()
+        .filter(|path| path.is_file())
+        .collect();
+    if paths.is_empty() {
+        return Err(anyhow::anyhow!("No files match {}", replacer.path));
+    }
+    Ok(paths)
+}
+
+/// 按配置的规则替换版本号，修改暂存在 `edits` 中；任何一个文件未匹配都会返回错误
+pub fn apply_replacers(
+    replacers: &[FileReplacer],
+    version: &Version,
+    previous: &Version,
+    edits: &mut FileEdits,
+) -> Result<()> {
+    let fill = |template: &str, escape: bool| {
+        let (current, previous) = (version.version.to_string(), previous.version.to_string());
+        let (current, previous) = if escape {
+            (regex::escape(&current), regex::escape(&previous))
+        } else {
+            (current, previous)
+        };
+        template
+            .replace("{{version}}", &current)
+            .replace("{{previous}}", &previous)
+    };
+
+    for replacer in replacers {
+        let search = Regex::new(&fill(&replacer.search, true))
+            .with_context(|| format!("Invalid search pattern {}", replacer.search))?;
+        let replace = fill(&replacer.replace, false);
+
+        for path in replacer_paths(replacer)? {
+            let content = edits.read(&path)?;
+            if !search.is_match(&content) {
+                return Err(anyhow::anyhow!(
+                    "Pattern {} does not match {}",
+                    replacer.search,
+                    path.display()
+                ));
+            }
+            let updated = search.replace_all(&content, replace.as_str()).into_owned();
+            edits.write(&path, updated);
+            println!("✔ [Replaced] version in {}", path.display());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use toml_edit::Document;
+
+use super::config::{ConfigKey, GlobalConfig, HOST_KEYS};
+use super::layers::{load_layers, Layer};
+
+// 敏感信息存储
+//
+// GitHub token 不再以明文写入配置文件，而是优先保存在系统钥匙串中
+// （macOS Keychain、Windows 凭据管理器、Linux Secret Service）。
+// 钥匙串不可用时（例如没有桌面会话的服务器）退回到权限为 0600 的
+// `~/.rustytag/credentials` 文件。
+//
+// 也可以不保存 token，改为在使用时读取：
+// - `github_token_file`（或 `GITHUB_TOKEN_FILE`）：从文件读取
+// - `github_token_command`：执行命令获取，例如 `gh auth token`
+//
+// 除默认 token 外，`[hosts."github.corp.com"]` 可以为其他主机单独配置，
+// 按仓库远程地址的主机名自动选择。
+
+const SERVICE: &str = "rustytag";
+/// 默认 token 在钥匙串中的账户名，主机 token 使用主机名
+pub const DEFAULT_ACCOUNT: &str = "github_token";
+/// 默认 token 所属的主机，其他主机必须单独配置
+pub const DEFAULT_HOST: &str = "github.com";
+
+/// token 的保存位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenStore {
+    Keyring,
+    File(PathBuf),
+}
+
+impl std::fmt::Display for TokenStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Keyring => write!(f, "system keyring"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+fn credentials_path() -> PathBuf {
+    GlobalConfig::config_dir().join("credentials")
+}
+
+fn keyring_entry(account: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, account)
+}
+
+/// 退回保存的 token 文件，每个账户一个键
+fn read_credentials() -> Result<Document> {
+    let path = credentials_path();
+    if !path.exists() {
+        return Ok(Document::new());
+    }
+    fs::read_to_string(&path)?
+        .parse::<Document>()
+        .with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn write_credentials(credentials: &Document) -> Result<()> {
+    let path = credentials_path();
+    if credentials.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        return Ok(());
+    }
+    write_private(&path, &credentials.to_string())
+}
+
+/// 保存 token，返回实际保存的位置
+pub fn store_token(account: &str, token: &str) -> Result<TokenStore> {
+    let mut credentials = read_credentials()?;
+    if keyring_entry(account)
+        .and_then(|entry| entry.set_password(token))
+        .is_ok()
+    {
+        // 已经写入钥匙串，之前退回保存的副本不再需要
+        if credentials.remove(account).is_some() {
+            write_credentials(&credentials)?;
+        }
+        return Ok(TokenStore::Keyring);
+    }
+    credentials[account] = toml_edit::value(token);
+    write_credentials(&credentials)?;
+    Ok(TokenStore::File(credentials_path()))
+}
+
+/// 读取保存的 token 及其位置
+pub fn load_token(account: &str) -> Result<Option<(String, TokenStore)>> {
+    if let Ok(token) = keyring_entry(account).and_then(|entry| entry.get_password()) {
+        return Ok(Some((token, TokenStore::Keyring)));
+    }
+    let credentials = read_credentials()?;
+    Ok(credentials
+        .get(account)
+        .and_then(|item| item.as_str())
+        .map(|token| (token.to_string(), TokenStore::File(credentials_path()))))
+}
+
+/// 删除保存的 token，返回是否删除了内容
+pub fn delete_token(account: &str) -> Result<bool> {
+    let mut deleted = keyring_entry(account)
+        .and_then(|entry| entry.delete_credential())
+        .is_ok();
+    let mut credentials = read_credentials()?;
+    if credentials.remove(account).is_some() {
+        write_credentials(&credentials)?;
+        deleted = true;
+    }
+    Ok(deleted)
+}
+
+/// 按配置层优先级获取访问 `host` 的 token
+///
+/// `github_token`、`github_token_file`、`github_token_command` 中所在层最高的生效，
+/// 同一层中按此顺序优先。全局配置中该主机的配置优先于同一层的默认 token，
+/// 环境变量与 `-c` 仍可在单次运行中覆盖它；全局配置中的默认 token 只用于 github.com，
+/// 避免发送到其他主机。
+pub fn resolve_token(host: &str) -> Result<Option<String>> {
+    let keys = ["github_token", "github_token_file", "github_token_command"]
+        .map(ConfigKey::find)
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+    for layer in load_layers(true)?.iter().rev() {
+        if layer.layer == Layer::Global {
+            if let Some(profile) = layer.config.get("hosts").and_then(|h| h.get(host)) {
+                for key in HOST_KEYS.iter().filter(|key| key.name.starts_with("token")) {
+                    if let Some(value) = key.lookup(profile).and_then(|v| v.as_str()) {
+                        return read_credential(key, value).map(Some);
+                    }
+                }
+            }
+            if host != DEFAULT_HOST {
+                continue;
+            }
+        }
+        for key in &keys {
+            if let Some(value) = key.lookup(&layer.config).and_then(|v| v.as_str()) {
+                return read_credential(key, value).map(Some);
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// 根据配置项读取 token：直接使用、从文件读取或执行命令
+fn read_credential(key: &ConfigKey, value: &str) -> Result<String> {
+    match key.name.trim_start_matches("github_") {
+        "token_file" => read_token_file(Path::new(value)),
+        "token_command" => run_token_command(value),
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// 从文件读取 token，忽略首尾空白
+fn read_token_file(path: &Path) -> Result<String> {
+    let token = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read token from {}", path.display()))?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(anyhow::anyhow!("Token file {} is empty", path.display()));
+    }
+    Ok(token.to_string())
+}
+
+/// 执行命令获取 token，使用命令的标准输出
+fn run_token_command(command: &str) -> Result<String> {
+    let output = super::hooks::shell(command)
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .with_context(|| format!("Failed to run token command `{}`", command))?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Token command `{}` failed with {}",
+            command,
+            output.status
+        ));
+    }
+    let token = String::from_utf8(output.stdout)
+        .with_context(|| format!("Token command `{}` printed invalid UTF-8", command))?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Token command `{}` printed nothing",
+            command
+        ));
+    }
+    Ok(token.to_string())
+}
+
+/// 写入只有当前用户可以读写的文件
+pub fn write_private(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // 新文件创建时即为 0600，不会短暂以更宽松的权限存在
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    // 已存在的文件保留原来的权限，需要在写入内容前收紧
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+
+use super::config::{LocalConfig, PackageConfig};
+use super::version::Version;
+
+// 标签模板
+//
+// 模板由 `{version}` 与前后的固定文本组成，例如 `v{version}`、`release-{version}`；
+// 包的模板还可以使用 `{package}`，例如 `{package}/v{version}`。
+// 同一个模板既用于生成新标签，也用于匹配已有标签，不匹配的标签会被忽略。
+//
+// 没有配置 `tag_template`（或 `version_prefix`）时沿用旧的行为：
+// 任意标签中第一个数字之前的部分都视为前缀。
+
+/// 包标签的默认模板，例如 `api@1.4.0`
+const DEFAULT_PACKAGE_TEMPLATE: &str = "{package}@{version}";
+
+/// 解析后的标签模板，`{version}` 前后的文本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagTemplate {
+    prefix: String,
+    suffix: String,
+}
+
+impl TagTemplate {
+    /// 解析模板，`package` 为 `None` 时不允许使用 `{package}`
+    pub fn parse(template: &str, package: Option<&str>) -> Result<Self> {
+        let expanded = match package {
+            Some(package) => template.replace("{package}", package),
+            None if template.contains("{package}") => {
+                return Err(anyhow::anyhow!(
+                    "{{package}} can only be used in package tag templates: {}",
+                    template
+                ))
+            }
+            None => template.to_string(),
+        };
+        let (prefix, suffix) = expanded
+            .split_once("{version}")
+            .ok_or_else(|| anyhow::anyhow!("Tag template {} must contain {{version}}", template))?;
+        if [prefix, suffix]
+            .iter()
+            .any(|part| part.contains(['{', '}']))
+        {
+            return Err(anyhow::anyhow!(
+                "Tag template {} must contain {{version}} exactly once and no other placeholders",
+                template
+            ));
+        }
+        Ok(Self {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+        })
+    }
+
+    /// 根目录标签的模板，未配置时返回 `None`
+    ///
+    /// 只配置了 `version_prefix` 时等同于 `<prefix>{version}`。
+    pub fn for_root(config: &LocalConfig) -> Result<Option<Self>> {
+        let template = match (&config.tag_template, &config.version_prefix) {
+            (Some(template), _) => template.clone(),
+            (None, Some(prefix)) => format!("{}{{version}}", prefix),
+            (None, None) => return Ok(None),
+        };
+        Self::parse(&template, None)
+            .context("Invalid tag_template")
+            .map(Some)
+    }
+
+    /// 包标签的模板
+    ///
+    /// 依次使用包的 `tag_template`、`tag_prefix`、全局的 `package_tag_template`，
+    /// 默认为 `{package}@{version}`。
+    pub fn for_package(config: &LocalConfig, package: &PackageConfig) -> Result<Self> {
+        let template = match (&package.tag_template, &package.tag_prefix) {
+            (Some(template), _) => template.clone(),
+            (None, Some(prefix)) => format!("{}{{version}}", prefix),
+            (None, None) => config
+                .package_tag_template
+                .clone()
+                .unwrap_or_else(|| DEFAULT_PACKAGE_TEMPLATE.to_string()),
+        };
+        Self::parse(&template, Some(&package.name))
+            .with_context(|| format!("Invalid tag template for package {}", package.name))
+    }
+
+    /// 所有包标签的模板，用于从根目录标签中排除包标签
+    pub fn for_packages(config: &LocalConfig) -> Result<Vec<Self>> {
+        config
+            .packages
+            .iter()
+            .map(|package| Self::for_package(config, package))
+            .collect()
+    }
+
+    /// 按模板生成版本，`to_string()` 即为标签名
+    pub fn version(&self, version: semver::Version) -> Version {
+        let mut version = Version::new(version).with_prefix(self.prefix.clone());
+        version.suffix = self.suffix.clone();
+        version
+    }
+
+    /// 匹配标签，不符合模板或版本号无效时返回 `None`
+    pub fn match_tag(&self, tag: &str) -> Option<Version> {
+        let version = tag.strip_prefix(&self.prefix)?.strip_suffix(&self.suffix)?;
+        semver::Version::parse(version)
+            .ok()
+            .map(|version| self.version(version))
+    }
+
+    /// 解析用户输入的版本，可以是完整的标签名，也可以只是版本号
+    pub fn parse_input(&self, input: &str) -> Result<Version> {
+        if let Some(version) = self.match_tag(input) {
+            return Ok(version);
+        }
+        let version = semver::Version::parse(input).with_context(|| {
+            format!(
+                "Invalid version {}, expected a version or a tag like {}",
+                input,
+                self.version(semver::Version::new(1, 2, 3))
+            )
+        })?;
+        Ok(self.version(version))
+    }
+}
@@ -1,8 +1,21 @@
-use crate::utils::project::{ProjectFile, ProjectFileType};
+use crate::utils::cargo::{update_workspace, update_workspace_member, workspace_root};
+use crate::utils::config::{PackageConfig, VersionScheme, VersionSource};
+use crate::utils::edit::FileEdits;
+use crate::utils::json::{read_string_value, set_top_level_string};
+use crate::utils::lockfile::update_lock_files;
+use crate::utils::project::{
+    has_dynamic_version, pattern_range, version_range, ProjectFile, ProjectFileType,
+    HELM_APP_VERSION, PYPROJECT_VERSION_TABLES,
+};
+use crate::utils::replace::apply_replacers;
+use crate::utils::tag::TagTemplate;
 use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use regex::Regex;
 use semver;
-use std::fs;
-use toml_edit::{value, Document};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml_edit::{value, Document, Item};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BumpType {
@@ -74,6 +87,68 @@ impl Version {
             suffix: self.suffix.clone(),
         }
     }
+
+    /// 按日历版本升级：同一个月内递增 MICRO，进入新的月份时从 0 开始
+    pub fn bump_calendar(&self, scheme: VersionScheme, today: NaiveDate) -> Result<Self> {
+        let year = today.year() as u64;
+        let year = match scheme {
+            VersionScheme::CalverShortYear => year % 100,
+            _ => year,
+        };
+        let month = today.month() as u64;
+        let current = (self.version.major, self.version.minor);
+        let new_version = if current == (year, month) {
+            semver::Version::new(year, month, self.version.patch + 1)
+        } else if current < (year, month) {
+            semver::Version::new(year, month, 0)
+        } else {
+            return Err(anyhow::anyhow!(
+                "Latest version {} is ahead of the current date for {}",
+                self,
+                scheme
+            ));
+        };
+
+        Ok(Self {
+            version: new_version,
+            ..self.clone()
+        })
+    }
+
+    /// 检查版本号是否符合版本方案，用于 `-V` 指定的版本
+    pub fn check_scheme(&self, scheme: VersionScheme) -> Result<()> {
+        let years = match scheme {
+            VersionScheme::Semver => return Ok(()),
+            VersionScheme::CalverYear => 1000..=9999,
+            VersionScheme::CalverShortYear => 0..=99,
+        };
+        if !years.contains(&self.version.major) || !(1..=12).contains(&self.version.minor) {
+            return Err(anyhow::anyhow!(
+                "Version {} does not match version_scheme {}",
+                self,
+                scheme
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 按配置的版本方案计算下一个版本
+///
+/// `bump_type` 为 `None`（`bump` 命令）时，语义化版本升级 patch，日历版本按当前 UTC 日期升级；
+/// 日历版本不支持 patch/minor/major。
+pub fn next_version(latest: &Version, bump_type: Option<BumpType>) -> Result<Version> {
+    let scheme = crate::utils::config::LocalConfig::load()?
+        .version_scheme
+        .unwrap_or_default();
+    match (scheme, bump_type) {
+        (VersionScheme::Semver, bump_type) => Ok(latest.bump(bump_type.unwrap_or(BumpType::Patch))),
+        (scheme, None) => latest.bump_calendar(scheme, chrono::Utc::now().date_naive()),
+        (scheme, Some(_)) => Err(anyhow::anyhow!(
+            "version_scheme is {}, use `rustytag bump` or set the version with -V",
+            scheme
+        )),
+    }
 }
 
 impl std::fmt::Display for Version {
@@ -82,103 +157,372 @@ impl std::fmt::Display for Version {
     }
 }
 
-pub fn update_version_to_project(version: &Version) -> Result<()> {
-    let version_files = ProjectFile::detect_all()?;
+pub fn update_version_to_project(
+    version: &Version,
+    previous: &Version,
+    lenient: bool,
+) -> Result<()> {
+    // 所有修改都计算成功后再写入，任何一步失败都不会修改文件
+    let config = crate::utils::config::LocalConfig::load()?;
+    let mut edits = FileEdits::default();
+    apply_replacers(&config.files, version, previous, &mut edits)?;
+    update_project_files(&ProjectFile::detect_all()?, version, lenient, &mut edits)?;
+    edits.commit()?;
+    println!("✔ [Updated] version to {} in project files", version);
+    Ok(())
+}
+
+/// 只更新 monorepo 中单个包目录下的项目文件
+pub fn update_package_version(
+    package: &PackageConfig,
+    version: &Version,
+    lenient: bool,
+) -> Result<()> {
+    let files = ProjectFile::detect_in(Path::new(&package.path))?;
+    let mut edits = FileEdits::default();
+    update_project_files(&files, version, lenient, &mut edits)?;
+    edits.commit()?;
+    println!(
+        "✔ [Updated] version to {} in package {}",
+        version.version, package.name
+    );
+    Ok(())
+}
+
+/// 更新所有项目文件并逐个报告旧版本，修改暂存在 `edits` 中，由调用方统一写入
+///
+/// 任何文件缺少版本声明都会中止升级；`lenient` 为真时只给出警告，并丢弃该文件的修改。
+pub fn update_project_files(
+    version_files: &[ProjectFile],
+    version: &Version,
+    lenient: bool,
+    edits: &mut FileEdits,
+) -> Result<()> {
     for version_file in version_files {
-        let result = match version_file.file_type {
-            ProjectFileType::CargoToml => update_cargo_toml(version),
-            ProjectFileType::PackageJson => update_package_json(version),
-            ProjectFileType::PyProjectToml => update_pyproject_toml(version),
-            ProjectFileType::Other => update_rustytag_json(version),
-        };
+        let path = version_file.path.as_path();
+        let mut staged = edits.clone();
+        let result = update_project_file(version_file, version, &mut staged).and_then(|previous| {
+            update_lock_files(version_file, version, &mut staged)?;
+            Ok(previous)
+        });
+        if result.is_ok() {
+            *edits = staged;
+        }
 
-        if let Err(e) = result {
-            println!(
-                "⚠️  Failed to update version in {}: {}",
-                version_file.path.display(),
-                e
-            );
+        match result {
+            Ok(Some(previous)) => {
+                println!("  ✔ {}: {} → {}", path.display(), previous, version.version)
+            }
+            Ok(None) => println!("  ➖ {}: skipped", path.display()),
+            Err(e) if lenient => {
+                println!("⚠️  Failed to update version in {}: {}", path.display(), e)
+            }
+            Err(e) => {
+                return Err(e.context(format!(
+                    "Failed to update version in {} (use --lenient to skip)",
+                    path.display()
+                )))
+            }
         }
     }
-    println!("✔ [Updated] version to {} in project files", version);
     Ok(())
 }
 
-fn update_cargo_toml(version: &Version) -> Result<()> {
-    let cargo_toml = fs::read_to_string("Cargo.toml")?;
-    let mut doc = cargo_toml.parse::<Document>()?;
-
-    if let Some(package) = doc.get_mut("package") {
-        if let Some(package) = package.as_table_mut() {
-            package.insert("version", value(version.version.to_string()));
+/// 更新单个项目文件，返回修改前的版本号；版本由其他工具管理时返回 `None`
+fn update_project_file(
+    version_file: &ProjectFile,
+    version: &Version,
+    edits: &mut FileEdits,
+) -> Result<Option<String>> {
+    let path = version_file.path.as_path();
+    match version_file.file_type {
+        // 工作区成员（monorepo 中独立版本的包）只升级自身
+        ProjectFileType::CargoToml => match workspace_root(path)? {
+            Some(root) => update_workspace_member(&root, path, version, edits).map(Some),
+            None => {
+                update_workspace(path.parent().unwrap_or(Path::new(".")), version, edits).map(Some)
+            }
+        },
+        ProjectFileType::PackageJson | ProjectFileType::ComposerJson | ProjectFileType::Other => {
+            update_json_version(path, version, edits).map(Some)
         }
+        ProjectFileType::PyProjectToml => update_pyproject_toml(path, version, edits),
+        ProjectFileType::HelmChart => update_helm_chart(path, version, edits).map(Some),
+        file_type => update_text_version(path, file_type, version, edits).map(Some),
     }
+}
 
-    fs::write("Cargo.toml", doc.to_string())?;
-    Ok(())
+/// 更新 JSON 清单（package.json、composer.json、.rustytag.json）顶层的 `version`
+fn update_json_version(path: &Path, version: &Version, edits: &mut FileEdits) -> Result<String> {
+    let content = edits.read(path)?;
+    let previous = read_string_value(&content, &["version"])?
+        .ok_or_else(|| anyhow::anyhow!("No \"version\" key found in {}", path.display()))?;
+    let updated = set_top_level_string(&content, "version", &version.version.to_string())?;
+    edits.write(path, updated);
+    Ok(previous)
 }
 
-fn update_package_json(version: &Version) -> Result<()> {
-    let package_json = fs::read_to_string("package.json")?;
-    let mut json: serde_json::Value = serde_json::from_str(&package_json)?;
+/// 更新纯文本定位的版本号（pom.xml、MSBuild、Gradle、YAML 等）
+fn update_text_version(
+    path: &Path,
+    file_type: ProjectFileType,
+    version: &Version,
+    edits: &mut FileEdits,
+) -> Result<String> {
+    let content = edits.read(path)?;
+    let range = version_range(path, &content, file_type)?;
+    replace_range(path, content, range, version, edits)
+}
 
-    if let Some(obj) = json.as_object_mut() {
-        obj.insert(
-            "version".to_string(),
-            serde_json::Value::String(version.version.to_string()),
-        );
+/// 更新 Helm Chart.yaml 的 `version`，按配置同时更新 `appVersion`
+fn update_helm_chart(path: &Path, version: &Version, edits: &mut FileEdits) -> Result<String> {
+    let previous = update_text_version(path, ProjectFileType::HelmChart, version, edits)?;
+
+    let config = crate::utils::config::LocalConfig::load()?;
+    if config.helm_app_version.unwrap_or(false) {
+        let content = edits.read(path)?;
+        if Regex::new(HELM_APP_VERSION)?.is_match(&content) {
+            let range = pattern_range(path, &content, HELM_APP_VERSION)?;
+            replace_range(path, content, range, version, edits)?;
+        }
     }
+    Ok(previous)
+}
 
-    fs::write("package.json", serde_json::to_string_pretty(&json)?)?;
-    Ok(())
+/// 将文件中 `range` 处的文本替换为新版本并暂存，返回原文本
+fn replace_range(
+    path: &Path,
+    mut content: String,
+    range: Range<usize>,
+    version: &Version,
+    edits: &mut FileEdits,
+) -> Result<String> {
+    let previous = content[range.clone()].to_string();
+    content.replace_range(range, &version.version.to_string());
+    edits.write(path, content);
+    Ok(previous)
 }
 
-fn update_pyproject_toml(version: &Version) -> Result<()> {
-    let pyproject_toml = fs::read_to_string("pyproject.toml")?;
+fn update_pyproject_toml(
+    path: &Path,
+    version: &Version,
+    edits: &mut FileEdits,
+) -> Result<Option<String>> {
+    let pyproject_toml = edits.read(path)?;
     let mut doc = pyproject_toml.parse::<Document>()?;
 
-    if let Some(tool) = doc.get_mut("tool") {
-        if let Some(poetry) = tool.get_mut("poetry") {
-            if let Some(poetry) = poetry.as_table_mut() {
-                poetry.insert("version", value(version.version.to_string()));
-            }
+    let mut previous = None;
+    for table_path in PYPROJECT_VERSION_TABLES {
+        let item = toml_path_mut(doc.as_item_mut(), table_path)
+            .and_then(|table| table.as_table_like_mut())
+            .and_then(|table| table.get_mut("version"));
+        if let Some(item) = item.filter(|item| item.is_str()) {
+            previous.get_or_insert_with(|| item.as_str().unwrap_or("").to_string());
+            set_version_value(item, &version.version.to_string());
         }
     }
 
-    fs::write("pyproject.toml", doc.to_string())?;
-    Ok(())
+    if previous.is_none() {
+        if has_dynamic_version(&doc) {
+            println!(
+                "⚠️  {} declares dynamic = [\"version\"], the version is managed by the build backend",
+                path.display()
+            );
+            return Ok(None);
+        }
+        return Err(anyhow::anyhow!(
+            "No version key found in [project], [tool.poetry] or [tool.pdm] of {}",
+            path.display()
+        ));
+    }
+
+    edits.write(path, doc.to_string());
+    Ok(previous)
 }
 
-fn update_rustytag_json(version: &Version) -> Result<()> {
-    let rustytag_json = fs::read_to_string(".rustytag.json")?;
-    let mut json: serde_json::Value = serde_json::from_str(&rustytag_json)?;
+/// 按键路径查找 TOML 节点，不会创建缺失的表
+pub(crate) fn toml_path_mut<'a>(item: &'a mut Item, path: &[&str]) -> Option<&'a mut Item> {
+    path.iter().try_fold(item, |item, key| {
+        item.as_table_like_mut()
+            .and_then(|table| table.get_mut(key))
+    })
+}
 
-    if let Some(obj) = json.as_object_mut() {
-        obj.insert(
-            "version".to_string(),
-            serde_json::Value::String(version.version.to_string()),
-        );
+/// 替换 TOML 中的版本字符串，保留原有的空白与行尾注释
+pub(crate) fn set_version_value(item: &mut Item, version: &str) {
+    match item.as_value_mut() {
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = toml_edit::Value::from(version);
+            *existing.decor_mut() = decor;
+        }
+        None => *item = value(version),
     }
-
-    fs::write(".rustytag.json", serde_json::to_string_pretty(&json)?)?;
-    Ok(())
 }
 
+/// 获取当前版本，来源由 `version_source` 配置决定
+///
+/// 标签与项目文件都没有版本时使用初始版本 0.1.0。
 pub fn get_latest_version() -> Result<Version> {
-    let version = crate::utils::git::get_latest_tag()?;
+    let config = crate::utils::config::LocalConfig::load()?;
+    let source = config.version_source.unwrap_or_default();
+    let template = TagTemplate::for_root(&config)?;
+    let tag = crate::utils::git::get_latest_tag()?;
+    let manifest = match (source, &tag) {
+        (VersionSource::Tags, Some(_)) => None,
+        _ => get_manifest_version()?,
+    };
 
-    // Warn if prefix exists but not configured
-    if !version.prefix.is_empty() {
-        let config = crate::utils::config::LocalConfig::load()?;
-        if config.version_prefix.is_none() {
+    // 项目文件中的版本按标签模板生成标签名，没有模板时沿用最新标签的前缀
+    let as_tag = |version: semver::Version| match (&template, &tag) {
+        (Some(template), _) => template.version(version),
+        (None, Some(tag)) => Version {
+            version,
+            ..tag.clone()
+        },
+        (None, None) => Version::new(version),
+    };
+    let manifest = manifest.map(|(path, v)| (path, as_tag(v)));
+
+    let version = match (source, tag.clone(), manifest) {
+        (VersionSource::Max, Some(tag), Some((path, manifest))) => {
+            if manifest.version > tag.version {
+                println!(
+                    "ℹ️  {} declares {}, newer than tag {}",
+                    path.display(),
+                    manifest.version,
+                    tag
+                );
+                manifest
+            } else {
+                tag
+            }
+        }
+        (VersionSource::Manifest, _, Some((_, manifest))) => manifest,
+        (_, Some(tag), _) => tag,
+        (_, None, Some((path, manifest))) => {
             println!(
-                "⚠️  Detected tag prefix '{}' but not configured",
-                version.prefix
+                "⚠️ No tags found, using version {} from {}",
+                manifest.version,
+                path.display()
             );
-            println!("ℹ️  You can configure version prefix using:");
-            println!("   rustytag config -s VERSION_PREFIX={}", version.prefix);
+            manifest
         }
-    }
+        (_, None, None) => {
+            println!("⚠️ No tags found, using initial version");
+            as_tag(semver::Version::new(0, 1, 0))
+        }
+    };
 
     Ok(version)
 }
+
+/// 解析命令行中指定的版本
+///
+/// 配置了标签模板时可以只写版本号，例如模板为 `v{version}` 时 `1.2.3` 生成 `v1.2.3`。
+pub fn parse_version_input(input: &str) -> Result<Version> {
+    match TagTemplate::for_root(&crate::utils::config::LocalConfig::load()?)? {
+        Some(template) => template.parse_input(input),
+        None => Version::parse(input),
+    }
+}
+
+/// 检查命令行中指定的版本是否符合配置的版本方案
+pub fn check_version_scheme(version: &Version) -> Result<()> {
+    let scheme = crate::utils::config::LocalConfig::load()?
+        .version_scheme
+        .unwrap_or_default();
+    version.check_scheme(scheme)
+}
+
+/// 根目录项目文件中第一个可解析的版本号
+fn get_manifest_version() -> Result<Option<(PathBuf, semver::Version)>> {
+    for file in ProjectFile::detect_in(Path::new(""))? {
+        if let Ok(Some(version)) = file.read_version() {
+            return Ok(Some((file.path, version)));
+        }
+    }
+    Ok(None)
+}
+
+/// 获取包的当前版本，没有标签时使用初始版本
+pub fn get_latest_package_version(package: &PackageConfig) -> Result<Version> {
+    let repo = git2::Repository::open(".")?;
+    if let Some(version) = crate::utils::git::get_latest_package_tag(&repo, package)? {
+        return Ok(version);
+    }
+    println!(
+        "⚠️ No tags found for package {}, using initial version",
+        package.name
+    );
+    let template = TagTemplate::for_package(&crate::utils::config::LocalConfig::load()?, package)?;
+    Ok(template.version(semver::Version::new(0, 1, 0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calver(version: &str) -> Version {
+        Version::parse(version).unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn calendar_bump_increments_micro_within_month() {
+        let next = calver("2026.10.3")
+            .bump_calendar(VersionScheme::CalverYear, date(2026, 10, 19))
+            .unwrap();
+        assert_eq!(next.to_string(), "2026.10.4");
+    }
+
+    #[test]
+    fn calendar_bump_resets_micro_in_new_month() {
+        let next = calver("2026.10.3")
+            .bump_calendar(VersionScheme::CalverYear, date(2026, 11, 1))
+            .unwrap();
+        assert_eq!(next.to_string(), "2026.11.0");
+        let next = calver("v2026.12.5")
+            .bump_calendar(VersionScheme::CalverYear, date(2027, 1, 1))
+            .unwrap();
+        assert_eq!(next.to_string(), "v2027.1.0");
+    }
+
+    #[test]
+    fn calendar_bump_uses_short_year() {
+        let next = calver("26.10.0")
+            .bump_calendar(VersionScheme::CalverShortYear, date(2026, 10, 19))
+            .unwrap();
+        assert_eq!(next.to_string(), "26.10.1");
+    }
+
+    #[test]
+    fn calendar_bump_rejects_version_ahead_of_date() {
+        let result =
+            calver("2026.11.0").bump_calendar(VersionScheme::CalverYear, date(2026, 10, 19));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn scheme_check_validates_year_and_month() {
+        assert!(calver("2026.10.0")
+            .check_scheme(VersionScheme::CalverYear)
+            .is_ok());
+        assert!(calver("26.10.0")
+            .check_scheme(VersionScheme::CalverShortYear)
+            .is_ok());
+        assert!(calver("1.2.3")
+            .check_scheme(VersionScheme::CalverYear)
+            .is_err());
+        assert!(calver("2026.13.0")
+            .check_scheme(VersionScheme::CalverYear)
+            .is_err());
+        assert!(calver("2026.10.0")
+            .check_scheme(VersionScheme::CalverShortYear)
+            .is_err());
+        assert!(calver("1.2.3").check_scheme(VersionScheme::Semver).is_ok());
+    }
+}
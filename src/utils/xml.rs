@@ -0,0 +1,62 @@
+use std::ops::Range;
+
+// 保留格式的 XML 编辑
+//
+// 仅用于 pom.xml、.csproj 这类清单文件：按元素路径定位文本节点，
+// 只替换文本本身，其余内容（注释、缩进、属性）保持原样。
+
+/// 查找元素路径对应的文本范围（不含首尾空白）
+///
+/// 路径从根元素开始逐级匹配直接子元素，例如 `["project", "version"]`
+/// 只会匹配 `<project>` 下的 `<version>`，不会匹配 `<parent>` 或依赖中的版本。
+pub fn find_element_text(content: &str, path: &[&str]) -> Option<Range<usize>> {
+    let mut stack: Vec<&str> = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = content[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &content[start..];
+
+        // 跳过注释、CDATA、声明与处理指令
+        let skip_to = [
+            ("<!--", "-->"),
+            ("<![CDATA[", "]]>"),
+            ("<?", "?>"),
+            ("<!", ">"),
+        ]
+        .iter()
+        .find(|(open, _)| rest.starts_with(open))
+        .map(|(_, close)| rest.find(close).map(|i| start + i + close.len()));
+        if let Some(end) = skip_to {
+            pos = end?;
+            continue;
+        }
+
+        let end = start + rest.find('>')? + 1;
+        let tag = &content[start + 1..end - 1];
+        pos = end;
+
+        if let Some(name) = tag.strip_prefix('/') {
+            if stack.last() == Some(&name.trim()) {
+                stack.pop();
+            }
+            continue;
+        }
+        if tag.ends_with('/') {
+            continue;
+        }
+
+        let name = tag.split(|c: char| c.is_whitespace()).next().unwrap_or("");
+        stack.push(name);
+        if stack.as_slice() == path {
+            let text_end = pos + content[pos..].find('<')?;
+            if content[text_end..].starts_with("</") {
+                let text = &content[pos..text_end];
+                let leading = text.len() - text.trim_start().len();
+                return Some(pos + leading..pos + leading + text.trim().len());
+            }
+        }
+    }
+
+    None
+}